@@ -5,27 +5,179 @@ pragma solidity ^0.8.0;
  * @title Web3LancerPolkadotInterface
  * @dev Interface for interacting with the Polkadot Rust contract via precompile or FFI.
  *      Function selectors and argument types must match the Rust contract ABI.
+ *      Clients and freelancers are identified by msg.sender; the contract never takes
+ *      an identity as an argument.
  */
 interface IWeb3LancerPolkadot {
+    // Events
+    event JobCreated(uint32 indexed jobId, address indexed client, address indexed paymentToken, uint128 budget, bytes32 contentCid, uint32 category, uint64 deadline);
+    event JobFunded(uint32 indexed jobId, uint128 amount, uint128 escrowBalance);
+    event JobStatusChanged(uint32 indexed jobId, uint8 oldStatus, uint8 newStatus);
+    event EscrowReleased(uint32 indexed jobId, address indexed to, uint128 amount);
+    event FeeCollected(uint32 indexed jobId, uint128 amount);
+    event FeesWithdrawn(address indexed token, address indexed to, uint128 amount);
+    event ProposalSubmitted(uint32 indexed proposalId, uint32 indexed jobId, address indexed freelancer, uint128 bidAmount, bytes32 coverHash);
+    event ProposalUpdated(uint32 indexed proposalId, uint128 bidAmount, bytes32 coverHash);
+    event CounterOffered(uint32 indexed proposalId, address indexed from, uint32 round, uint128 amount, bytes32 termsHash);
+    event ProposalStatusChanged(uint32 indexed proposalId, uint8 oldStatus, uint8 newStatus);
+    event AgreementCreated(uint32 indexed agreementId, uint32 jobId, address indexed client, address indexed freelancer, uint128 totalAmount);
+    event AgreementStatusChanged(uint32 indexed agreementId, uint8 oldStatus, uint8 newStatus);
+    event DisputeResolved(uint32 indexed agreementId, uint16 clientShareBps, uint128 clientAmount, uint128 freelancerAmount);
+    event OwnershipTransferStarted(address indexed previousOwner, address indexed newOwner);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    event Paused(address account);
+    event Unpaused(address account);
+    event Upgraded(bytes32 indexed codeHash);
+    event StorageMigrated(uint8 version);
+    event ConfigUpdated(address arbitrator, uint16 feeBps, uint128 minJobBudget, uint32 maxMilestones);
+    event ReviewSubmitted(uint32 indexed agreementId, address indexed reviewer, address indexed reviewee, uint8 score, bytes32 reviewHash);
+    event MilestoneStatusChanged(uint32 indexed agreementId, uint32 indexed index, uint8 oldStatus, uint8 newStatus);
+
+    // Errors (revert data of every failed call)
+    error InvalidOperation();
+    error NotFound(uint32 id);
+    error AlreadyExists();
+    error StorageFull();
+    error InvalidInput();
+    error Unauthorized(address caller);
+    error TransferFailed(address to, uint128 amount);
+    error InvalidTransition(uint8 from, uint8 to);
+    error InsufficientEscrow(uint128 available, uint128 required);
+    error UnknownSelector(bytes4 selector);
+    error DeadlinePassed(uint64 deadline);
+    error EnforcedPause();
+
     // Job Management
-    function createJob(uint32 clientId, uint128 budget) external returns (uint32 jobId);
-    function getJob(uint32 jobId) external view returns (uint32 clientId, uint128 budget, uint8 status);
+    // createJob and fundJob lock msg.value in escrow for the job. Releasing milestones
+    // pays the freelancer from it; cancelling the job refunds the client.
+    // contentCid is the sha2-256 digest of the IPFS CIDv1 holding the title and description.
+    // deadline (delivery) and biddingDeadline (last moment to submit proposals) are unix
+    // seconds, compared against block.timestamp; 0 means none.
+    // budget is the most the client means to pay: higher bids are taken but flagged overBudget in
+    // getProposal, which follows the negotiated amount once countered. Bids below minBudget
+    // (which may not exceed budget) are rejected.
+    // paymentToken is the ERC-20 the job is paid in, or address(0) for the native token.
+    // Token jobs take no msg.value: approve the contract, then call fundJobWithToken, which
+    // pulls the tokens with transferFrom. Payouts and refunds use transfer. The token must
+    // return true from both; tokens that return nothing are not supported.
+    function createJob(uint128 budget, bytes32 contentCid, uint32 category, uint64 deadline, uint64 biddingDeadline, address paymentToken, uint128 minBudget) external payable returns (uint32 jobId);
+    function getJob(uint32 jobId) external view returns (address client, uint128 budget, uint8 status, bytes32 contentCid, uint32 category, uint64 deadline, uint64 biddingDeadline, address paymentToken, uint128 minBudget);
+    // Only cancels an Open job (client or owner). Work in progress ends through its agreement.
     function updateJobStatus(uint32 jobId, uint8 newStatus) external returns (bool success);
+    function fundJob(uint32 jobId) external payable returns (uint128 escrowBalance);
+    function fundJobWithToken(uint32 jobId, uint128 amount) external returns (uint128 escrowBalance);
+    function getEscrowBalance(uint32 jobId) external view returns (uint128 balance);
+    // Anyone may cancel an Open job once its deadline has passed, or 7 days after its biddingDeadline
+    // (time for the client to accept a bid); the escrow goes back to the client.
+    function expireJob(uint32 jobId) external returns (bool success);
+
+    // List views return the total number of matches and one page of ids (at most 50; larger
+    // limits are clamped). statusFilter 0xff matches jobs in any status.
+    function listJobs(uint32 offset, uint32 limit, uint8 statusFilter) external view returns (uint32 total, uint32[] memory jobIds);
 
     // Proposal Management
-    function submitProposal(uint32 jobId, uint32 freelancerId, uint128 bidAmount) external returns (uint32 proposalId);
-    function getProposal(uint32 proposalId) external view returns (uint32 jobId, uint32 freelancerId, uint128 bidAmount, uint8 status);
+    // After expiresAt (unix seconds, 0 = never) the client can no longer accept the proposal.
+    // coverHash is the sha2-256 digest of the IPFS CIDv1 holding the cover letter.
+    // Proposal status: 0 Submitted, 1 Accepted, 2 Rejected, 3 Withdrawn.
+    // Each freelancer gets one proposal per job; a second one reverts with AlreadyExists
+    // unless the first was withdrawn. getProposalByFreelancer returns the latest one.
+    function submitProposal(uint32 jobId, uint128 bidAmount, uint64 expiresAt, bytes32 coverHash) external returns (uint32 proposalId);
+    function getProposal(uint32 proposalId) external view returns (uint32 jobId, address freelancer, uint128 bidAmount, uint8 status, uint64 expiresAt, bytes32 coverHash, bool overBudget);
+    // Accepting needs the job to still be Open.
     function updateProposalStatus(uint32 proposalId, uint8 newStatus) external returns (bool success);
+    // The freelancer can revise or withdraw a proposal while it is still Submitted;
+    // revising also needs the job to be Open and before its biddingDeadline.
+    function updateProposal(uint32 proposalId, uint128 newBid, bytes32 newCoverHash) external returns (bool success);
+    function withdrawProposal(uint32 proposalId) external returns (bool success);
+    // Negotiation. The client may answer a Submitted proposal with a counter-offer, then the
+    // freelancer and the client take turns. Whoever's turn it is may also accept or reject the
+    // latest offer with updateProposalStatus (the client can always reject). Once countered,
+    // the bid can no longer be revised with updateProposal. The agreement is made for
+    // currentAmount: the last counter-offer, or the bid if there was none. The freelancer's
+    // counter-offers may not go below the job's minBudget.
+    function counterOffer(uint32 proposalId, uint128 amount, bytes32 termsHash) external returns (uint32 round);
+    function getNegotiation(uint32 proposalId) external view returns (uint32 roundCount, uint128 currentAmount);
+    function getCounterOffer(uint32 proposalId, uint32 round) external view returns (address from, uint128 amount, bytes32 termsHash);
+    function listProposalsForJob(uint32 jobId, uint32 offset, uint32 limit) external view returns (uint32 total, uint32[] memory proposalIds);
+    function getProposalByFreelancer(uint32 jobId, address freelancer) external view returns (bool exists, uint32 proposalId);
 
     // Agreement Management
-    function createAgreementFromProposal(uint32 proposalId) external returns (uint32 agreementId);
-    function getAgreement(uint32 agreementId) external view returns (uint32 jobId, uint32 clientId, uint32 freelancerId, uint128 totalAmount, uint8 status);
+    // amounts[i] and descriptionHashes[i] describe milestone i; the amounts must add up to the
+    // agreed amount (getNegotiation's currentAmount). Empty arrays create a single milestone for all of it.
+    // Starting the job rejects its other Submitted or Accepted proposals.
+    function createAgreementFromProposal(uint32 proposalId, uint128[] calldata amounts, bytes32[] calldata descriptionHashes) external returns (uint32 agreementId);
+    // Does updateProposalStatus(Accepted) and createAgreementFromProposal in one transaction,
+    // adding msg.value to the job's escrow first. A proposal the freelancer already accepted
+    // (after a counter-offer) skips the first step.
+    // Client only; reverts as a whole if any step fails.
+    function acceptProposal(uint32 proposalId, uint128[] calldata amounts, bytes32[] calldata descriptionHashes) external payable returns (uint32 agreementId);
+    function getAgreement(uint32 agreementId) external view returns (uint32 jobId, address client, address freelancer, uint128 totalAmount, uint8 status, uint32 milestoneCount);
+    // Only disputes go through here, raised by the client or the freelancer; an agreement
+    // completes when its last milestone is released.
     function updateAgreementStatus(uint32 agreementId, uint8 newStatus) external returns (bool success);
+    function listAgreementsForUser(address user, uint32 offset, uint32 limit) external view returns (uint32 total, uint32[] memory agreementIds);
+
+    // Milestones (status: 0 Pending, 1 Submitted, 2 Approved, 3 Paid)
+    // The freelancer submits; only the client approves and releases. Contested milestones go to a dispute.
+    function getMilestone(uint32 agreementId, uint32 index) external view returns (uint128 amount, bytes32 descriptionHash, uint8 status);
+    function submitMilestone(uint32 agreementId, uint32 index) external returns (bool success);
+    function approveMilestone(uint32 agreementId, uint32 index) external returns (bool success);
+    function releaseMilestone(uint32 agreementId, uint32 index) external returns (bool success);
+
+    // Platform fee: feeBps from the config is fixed when an agreement is made and taken from every
+    // payout to the freelancer (milestone releases and their share of a dispute) into the treasury.
+    // freelancerAmount = totalAmount - platformFee once every milestone is released. platformFee and
+    // freelancerAmount project the full schedule; after a dispute, DisputeResolved has the actual split.
+    function getPayoutBreakdown(uint32 agreementId) external view returns (uint128 totalAmount, uint16 feeBps, uint128 platformFee, uint128 freelancerAmount, uint128 releasedAmount);
+
+    // Disputes (agreement status: 0 Active, 1 Completed, 2 Disputed, 3 Resolved)
+    // The arbitrator splits the unpaid milestone amounts; clientShareBps is out of 10_000.
+    // The arbitrator is part of the admin config below. The job ends Completed if the freelancer
+    // was paid anything (milestones or this split) and Cancelled otherwise.
+    function resolveDispute(uint32 agreementId, uint16 clientShareBps) external returns (bool success);
+
+    // Reputation
+    // Each party of a Completed agreement rates the other once, score 1-5.
+    // Average rating = ratingSum / ratingCount.
+    function rateCounterparty(uint32 agreementId, uint8 score, bytes32 reviewHash) external returns (bool success);
+    function getReputation(address account) external view returns (uint32 ratingCount, uint32 ratingSum, uint32 completedJobs, uint32 disputes);
+    function getReview(uint32 agreementId, address reviewer) external view returns (uint8 score, bytes32 reviewHash);
+
+    // Admin
+    // Deploy input (optional): abi.encode(address owner, address arbitrator, uint16 feeBps, uint128 minJobBudget, uint32 maxMilestones).
+    // A zero owner means the deployer, a zero arbitrator means the owner. feeBps is at most 1_000, maxMilestones 1-16.
+    // Ownership moves in two steps: transferOwnership, then acceptOwnership by the new owner.
+    function getConfig() external view returns (address owner, address pendingOwner, address arbitrator, uint16 feeBps, uint128 minJobBudget, uint32 maxMilestones);
+    function setConfig(address arbitrator, uint16 feeBps, uint128 minJobBudget, uint32 maxMilestones) external returns (bool success);
+    function transferOwnership(address newOwner) external returns (bool success);
+    function acceptOwnership() external returns (bool success);
+
+    // Emergency stop. While paused every state-changing call reverts with EnforcedPause,
+    // except the admin calls and cancelling (updateJobStatus to 3) or expiring an Open job, which refund its escrow.
+    function pause() external returns (bool success);
+    function unpause() external returns (bool success);
+    function paused() external view returns (bool isPaused);
+
+    // Treasury of collected platform fees, kept per payment token
+    function withdrawFees(address to, uint128 amount) external returns (bool success);
+    function getTreasuryBalance() external view returns (uint128 balance);
+    function withdrawTokenFees(address token, address to, uint128 amount) external returns (bool success);
+    function getTokenTreasuryBalance(address token) external view returns (uint128 balance);
+
+    // Upgrades. upgrade swaps in already uploaded code, keeping address and storage.
+    // If the new code writes a newer storage version, call migrate until done is true;
+    // records not migrated yet are still readable and get rewritten when next saved.
+    // migrate reverts with InvalidOperation on a record it cannot decode and stays incomplete.
+    function upgrade(bytes32 codeHash) external returns (bool success);
+    function migrate(uint32 maxRecords) external returns (uint32 migrated, bool done);
+    function getStorageVersion() external view returns (uint8 storedVersion, uint8 codeVersion);
 }
 
 /**
  * @title Web3LancerCaller
  * @dev Example contract that calls into the Polkadot Rust contract for Web3Lancer logic.
+ *      Note that the Rust contract sees this contract as the caller, so jobs and
+ *      proposals created through it belong to this contract's address.
  */
 contract Web3LancerCaller {
     IWeb3LancerPolkadot public polkadotContract;
@@ -35,11 +187,11 @@ contract Web3LancerCaller {
     }
 
     // --- Job Management ---
-    function createJob(uint32 clientId, uint128 budget) external returns (uint32) {
-        return polkadotContract.createJob(clientId, budget);
+    function createJob(uint128 budget, bytes32 contentCid, uint32 category, uint64 deadline, uint64 biddingDeadline, address paymentToken, uint128 minBudget) external payable returns (uint32) {
+        return polkadotContract.createJob{value: msg.value}(budget, contentCid, category, deadline, biddingDeadline, paymentToken, minBudget);
     }
 
-    function getJob(uint32 jobId) external view returns (uint32, uint128, uint8) {
+    function getJob(uint32 jobId) external view returns (address, uint128, uint8, bytes32, uint32, uint64, uint64, address, uint128) {
         return polkadotContract.getJob(jobId);
     }
 
@@ -47,12 +199,31 @@ contract Web3LancerCaller {
         return polkadotContract.updateJobStatus(jobId, newStatus);
     }
 
+    function fundJob(uint32 jobId) external payable returns (uint128) {
+        return polkadotContract.fundJob{value: msg.value}(jobId);
+    }
+
+    function getEscrowBalance(uint32 jobId) external view returns (uint128) {
+        return polkadotContract.getEscrowBalance(jobId);
+    }
+
+    function expireJob(uint32 jobId) external returns (bool) {
+        return polkadotContract.expireJob(jobId);
+    }
+
+    function listJobs(uint32 offset, uint32 limit, uint8 statusFilter) external view returns (uint32, uint32[] memory) {
+        return polkadotContract.listJobs(offset, limit, statusFilter);
+    }
+
+    // Refunds from cancelled jobs created through this contract are paid to it
+    receive() external payable {}
+
     // --- Proposal Management ---
-    function submitProposal(uint32 jobId, uint32 freelancerId, uint128 bidAmount) external returns (uint32) {
-        return polkadotContract.submitProposal(jobId, freelancerId, bidAmount);
+    function submitProposal(uint32 jobId, uint128 bidAmount, uint64 expiresAt, bytes32 coverHash) external returns (uint32) {
+        return polkadotContract.submitProposal(jobId, bidAmount, expiresAt, coverHash);
     }
 
-    function getProposal(uint32 proposalId) external view returns (uint32, uint32, uint128, uint8) {
+    function getProposal(uint32 proposalId) external view returns (uint32, address, uint128, uint8, uint64, bytes32, bool) {
         return polkadotContract.getProposal(proposalId);
     }
 
@@ -60,16 +231,100 @@ contract Web3LancerCaller {
         return polkadotContract.updateProposalStatus(proposalId, newStatus);
     }
 
+    function updateProposal(uint32 proposalId, uint128 newBid, bytes32 newCoverHash) external returns (bool) {
+        return polkadotContract.updateProposal(proposalId, newBid, newCoverHash);
+    }
+
+    function withdrawProposal(uint32 proposalId) external returns (bool) {
+        return polkadotContract.withdrawProposal(proposalId);
+    }
+
+    function counterOffer(uint32 proposalId, uint128 amount, bytes32 termsHash) external returns (uint32) {
+        return polkadotContract.counterOffer(proposalId, amount, termsHash);
+    }
+
+    function getNegotiation(uint32 proposalId) external view returns (uint32, uint128) {
+        return polkadotContract.getNegotiation(proposalId);
+    }
+
+    function listProposalsForJob(uint32 jobId, uint32 offset, uint32 limit) external view returns (uint32, uint32[] memory) {
+        return polkadotContract.listProposalsForJob(jobId, offset, limit);
+    }
+
+    function getProposalByFreelancer(uint32 jobId, address freelancer) external view returns (bool, uint32) {
+        return polkadotContract.getProposalByFreelancer(jobId, freelancer);
+    }
+
     // --- Agreement Management ---
-    function createAgreementFromProposal(uint32 proposalId) external returns (uint32) {
-        return polkadotContract.createAgreementFromProposal(proposalId);
+    function createAgreementFromProposal(uint32 proposalId, uint128[] calldata amounts, bytes32[] calldata descriptionHashes) external returns (uint32) {
+        return polkadotContract.createAgreementFromProposal(proposalId, amounts, descriptionHashes);
+    }
+
+    function acceptProposal(uint32 proposalId, uint128[] calldata amounts, bytes32[] calldata descriptionHashes) external payable returns (uint32) {
+        return polkadotContract.acceptProposal{value: msg.value}(proposalId, amounts, descriptionHashes);
     }
 
-    function getAgreement(uint32 agreementId) external view returns (uint32, uint32, uint32, uint128, uint8) {
+    function getAgreement(uint32 agreementId) external view returns (uint32, address, address, uint128, uint8, uint32) {
         return polkadotContract.getAgreement(agreementId);
     }
 
     function updateAgreementStatus(uint32 agreementId, uint8 newStatus) external returns (bool) {
         return polkadotContract.updateAgreementStatus(agreementId, newStatus);
     }
+
+    function listAgreementsForUser(address user, uint32 offset, uint32 limit) external view returns (uint32, uint32[] memory) {
+        return polkadotContract.listAgreementsForUser(user, offset, limit);
+    }
+
+    // --- Milestones ---
+    function getMilestone(uint32 agreementId, uint32 index) external view returns (uint128, bytes32, uint8) {
+        return polkadotContract.getMilestone(agreementId, index);
+    }
+
+    function submitMilestone(uint32 agreementId, uint32 index) external returns (bool) {
+        return polkadotContract.submitMilestone(agreementId, index);
+    }
+
+    function approveMilestone(uint32 agreementId, uint32 index) external returns (bool) {
+        return polkadotContract.approveMilestone(agreementId, index);
+    }
+
+    function releaseMilestone(uint32 agreementId, uint32 index) external returns (bool) {
+        return polkadotContract.releaseMilestone(agreementId, index);
+    }
+
+    function getPayoutBreakdown(uint32 agreementId) external view returns (uint128, uint16, uint128, uint128, uint128) {
+        return polkadotContract.getPayoutBreakdown(agreementId);
+    }
+
+    // --- Disputes ---
+    function resolveDispute(uint32 agreementId, uint16 clientShareBps) external returns (bool) {
+        return polkadotContract.resolveDispute(agreementId, clientShareBps);
+    }
+
+    // --- Reputation ---
+    function rateCounterparty(uint32 agreementId, uint8 score, bytes32 reviewHash) external returns (bool) {
+        return polkadotContract.rateCounterparty(agreementId, score, reviewHash);
+    }
+
+    function getReputation(address account) external view returns (uint32, uint32, uint32, uint32) {
+        return polkadotContract.getReputation(account);
+    }
+
+    function getReview(uint32 agreementId, address reviewer) external view returns (uint8, bytes32) {
+        return polkadotContract.getReview(agreementId, reviewer);
+    }
+
+    // --- Admin ---
+    function getConfig() external view returns (address, address, address, uint16, uint128, uint32) {
+        return polkadotContract.getConfig();
+    }
+
+    function paused() external view returns (bool) {
+        return polkadotContract.paused();
+    }
+
+    function getStorageVersion() external view returns (uint8, uint8) {
+        return polkadotContract.getStorageVersion();
+    }
 }
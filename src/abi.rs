@@ -0,0 +1,302 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/abi.rs
+
+// Solidity ABI codec.
+// Every value occupies one 32-byte word (head). Dynamic values (`bytes`, `T[]`) put an
+// offset into the head and their length + data into the tail. Offsets are relative to
+// the start of the enclosing tuple, i.e. the call data right after the selector.
+// No allocator is available, so the encoder writes into a caller-provided buffer.
+
+use crate::types::ContractError;
+
+pub const WORD: usize = 32;
+
+/// A static ABI type that fits in a single 32-byte word.
+pub trait Word: Sized {
+    fn to_word(&self) -> [u8; WORD];
+    /// Returns None if the word is not a valid encoding (e.g. dirty high bytes).
+    fn from_word(word: &[u8; WORD]) -> Option<Self>;
+}
+
+macro_rules! impl_uint_word {
+    ($($t:ty),*) => {$(
+        impl Word for $t {
+            fn to_word(&self) -> [u8; WORD] {
+                let mut word = [0u8; WORD];
+                let bytes = self.to_be_bytes();
+                word[WORD - bytes.len()..].copy_from_slice(&bytes);
+                word
+            }
+
+            fn from_word(word: &[u8; WORD]) -> Option<Self> {
+                const SIZE: usize = core::mem::size_of::<$t>();
+                if word[..WORD - SIZE].iter().any(|b| *b != 0) {
+                    return None; // Value does not fit in this type
+                }
+                Some(<$t>::from_be_bytes(word[WORD - SIZE..].try_into().ok()?))
+            }
+        }
+    )*};
+}
+
+impl_uint_word!(u8, u16, u32, u64, u128);
+
+impl Word for bool {
+    fn to_word(&self) -> [u8; WORD] {
+        (*self as u8).to_word()
+    }
+
+    fn from_word(word: &[u8; WORD]) -> Option<Self> {
+        match u8::from_word(word)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+/// `address`: 20 bytes, right-aligned.
+impl Word for [u8; 20] {
+    fn to_word(&self) -> [u8; WORD] {
+        let mut word = [0u8; WORD];
+        word[12..].copy_from_slice(self);
+        word
+    }
+
+    fn from_word(word: &[u8; WORD]) -> Option<Self> {
+        if word[..12].iter().any(|b| *b != 0) {
+            return None;
+        }
+        word[12..].try_into().ok()
+    }
+}
+
+/// `bytes4`: 4 bytes, left-aligned.
+impl Word for [u8; 4] {
+    fn to_word(&self) -> [u8; WORD] {
+        let mut word = [0u8; WORD];
+        word[..4].copy_from_slice(self);
+        word
+    }
+
+    fn from_word(word: &[u8; WORD]) -> Option<Self> {
+        if word[4..].iter().any(|b| *b != 0) {
+            return None;
+        }
+        word[..4].try_into().ok()
+    }
+}
+
+/// `bytes32`: the word itself.
+impl Word for [u8; 32] {
+    fn to_word(&self) -> [u8; WORD] {
+        *self
+    }
+
+    fn from_word(word: &[u8; WORD]) -> Option<Self> {
+        Some(*word)
+    }
+}
+
+// --- Decoding ---
+
+/// Reads arguments from an ABI-encoded tuple, one head word at a time.
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Decoder { data, pos: 0 }
+    }
+
+    fn word_at(&self, offset: usize) -> Result<&'a [u8; WORD], ContractError> {
+        let end = offset.checked_add(WORD).ok_or(ContractError::InvalidInput)?;
+        self.data
+            .get(offset..end)
+            .and_then(|w| w.try_into().ok())
+            .ok_or(ContractError::InvalidInput)
+    }
+
+    fn next_word(&mut self) -> Result<&'a [u8; WORD], ContractError> {
+        let word = self.word_at(self.pos)?;
+        self.pos += WORD;
+        Ok(word)
+    }
+
+    /// Reads the next static value.
+    pub fn read<T: Word>(&mut self) -> Result<T, ContractError> {
+        T::from_word(self.next_word()?).ok_or(ContractError::InvalidInput)
+    }
+
+    /// Follows a head offset and returns (length, position right after the length word).
+    fn read_dynamic_header(&mut self) -> Result<(usize, usize), ContractError> {
+        let offset = self.read::<u32>()? as usize;
+        let len = u32::from_word(self.word_at(offset)?).ok_or(ContractError::InvalidInput)? as usize;
+        Ok((len, offset + WORD))
+    }
+
+    /// Reads a dynamic `bytes` value.
+    pub fn read_bytes(&mut self) -> Result<&'a [u8], ContractError> {
+        let (len, start) = self.read_dynamic_header()?;
+        let end = start.checked_add(len).ok_or(ContractError::InvalidInput)?;
+        self.data.get(start..end).ok_or(ContractError::InvalidInput)
+    }
+
+    /// Reads a dynamic array of static elements.
+    /// Returns the element count and a decoder positioned at the first element.
+    pub fn read_array(&mut self) -> Result<(usize, Decoder<'a>), ContractError> {
+        let (len, start) = self.read_dynamic_header()?;
+        let end = len
+            .checked_mul(WORD)
+            .and_then(|size| start.checked_add(size))
+            .ok_or(ContractError::InvalidInput)?;
+        let elements = self.data.get(start..end).ok_or(ContractError::InvalidInput)?;
+        Ok((len, Decoder::new(elements)))
+    }
+}
+
+// --- Encoding ---
+
+/// Writes an ABI-encoded tuple into `buf`.
+/// `head_words` is the number of top-level values; dynamic values go after them.
+pub struct Encoder<'a> {
+    buf: &'a mut [u8],
+    head: usize,
+    tail: usize,
+    overflow: bool,
+}
+
+impl<'a> Encoder<'a> {
+    pub fn new(buf: &'a mut [u8], head_words: usize) -> Self {
+        let tail = head_words * WORD;
+        let overflow = tail > buf.len();
+        Encoder { buf, head: 0, tail, overflow }
+    }
+
+    fn write_word(&mut self, at: usize, word: &[u8; WORD]) {
+        match self.buf.get_mut(at..at + WORD) {
+            Some(slot) => slot.copy_from_slice(word),
+            None => self.overflow = true,
+        }
+    }
+
+    fn push_head(&mut self, word: &[u8; WORD]) {
+        self.write_word(self.head, word);
+        self.head += WORD;
+    }
+
+    /// Appends a static value to the head.
+    pub fn push<T: Word>(&mut self, value: T) -> &mut Self {
+        self.push_head(&value.to_word());
+        self
+    }
+
+    /// Appends a dynamic `bytes` value (offset in the head, data in the tail).
+    pub fn push_bytes(&mut self, data: &[u8]) -> &mut Self {
+        self.push_head(&(self.tail as u32).to_word());
+        self.write_word(self.tail, &(data.len() as u32).to_word());
+        self.tail += WORD;
+
+        let padded = data.len().div_ceil(WORD) * WORD;
+        match self.buf.get_mut(self.tail..self.tail + padded) {
+            Some(slot) => {
+                slot.fill(0);
+                slot[..data.len()].copy_from_slice(data);
+            }
+            None => self.overflow = true,
+        }
+        self.tail += padded;
+        self
+    }
+
+    /// Appends a dynamic array of static elements.
+    pub fn push_array<T: Word + Copy>(&mut self, items: &[T]) -> &mut Self {
+        self.push_head(&(self.tail as u32).to_word());
+        self.write_word(self.tail, &(items.len() as u32).to_word());
+        self.tail += WORD;
+        for item in items {
+            self.write_word(self.tail, &item.to_word());
+            self.tail += WORD;
+        }
+        self
+    }
+
+    /// Returns the total encoded length.
+    pub fn finish(&mut self) -> Result<usize, ContractError> {
+        if self.overflow {
+            return Err(ContractError::InvalidOperation); // Output buffer too small
+        }
+        Ok(self.tail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (uint32 id, uint128[] amounts) encoded by the Encoder.
+    fn encoded(buf: &mut [u8; 8 * WORD]) -> usize {
+        Encoder::new(buf, 2).push(7u32).push_array(&[300u128, 500]).finish().unwrap()
+    }
+
+    #[test]
+    fn arrays_round_trip() {
+        let mut buf = [0u8; 8 * WORD];
+        let len = encoded(&mut buf);
+        assert_eq!(len, 5 * WORD);
+
+        let mut decoder = Decoder::new(&buf[..len]);
+        assert_eq!(decoder.read::<u32>(), Ok(7));
+        let (count, mut elements) = decoder.read_array().unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(elements.read::<u128>(), Ok(300));
+        assert_eq!(elements.read::<u128>(), Ok(500));
+        assert_eq!(elements.read::<u128>(), Err(ContractError::InvalidInput));
+    }
+
+    #[test]
+    fn array_offset_must_point_into_the_data() {
+        let mut buf = [0u8; 8 * WORD];
+        let len = encoded(&mut buf);
+
+        for offset in [len as u32, u32::MAX] {
+            buf[WORD..2 * WORD].copy_from_slice(&offset.to_word());
+            let mut decoder = Decoder::new(&buf[..len]);
+            decoder.read::<u32>().unwrap();
+            assert_eq!(decoder.read_array().err(), Some(ContractError::InvalidInput));
+        }
+
+        // Offsets wider than u32 are rejected rather than truncated
+        buf[WORD..2 * WORD].copy_from_slice(&((1u64 << 32) + 2 * WORD as u64).to_word());
+        let mut decoder = Decoder::new(&buf[..len]);
+        decoder.read::<u32>().unwrap();
+        assert_eq!(decoder.read_array().err(), Some(ContractError::InvalidInput));
+    }
+
+    #[test]
+    fn array_length_must_fit_the_data() {
+        let mut buf = [0u8; 8 * WORD];
+        let len = encoded(&mut buf);
+
+        for count in [3u32, u32::MAX] {
+            buf[2 * WORD..3 * WORD].copy_from_slice(&count.to_word());
+            let mut decoder = Decoder::new(&buf[..len]);
+            decoder.read::<u32>().unwrap();
+            assert_eq!(decoder.read_array().err(), Some(ContractError::InvalidInput));
+        }
+
+        // Elements cut off mid-word
+        buf[2 * WORD..3 * WORD].copy_from_slice(&2u32.to_word());
+        let mut decoder = Decoder::new(&buf[..len - 1]);
+        decoder.read::<u32>().unwrap();
+        assert_eq!(decoder.read_array().err(), Some(ContractError::InvalidInput));
+    }
+
+    #[test]
+    fn encoder_reports_a_short_buffer() {
+        let mut buf = [0u8; 4 * WORD];
+        let result = Encoder::new(&mut buf, 2).push(7u32).push_array(&[300u128, 500]).finish();
+        assert_eq!(result, Err(ContractError::InvalidOperation));
+    }
+}
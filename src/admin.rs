@@ -0,0 +1,343 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/admin.rs
+
+// Contract ownership and admin configuration.
+// The owner and the initial Config are written by the constructor. Ownership moves in two
+// steps (transferOwnership, then acceptOwnership by the new owner) so it can't be handed to
+// a mistyped address.
+
+use crate::abi::Decoder;
+use crate::agreement_management::MAX_MILESTONES;
+use crate::escrow::{self, BPS_DENOMINATOR};
+use crate::types::{Address, Balance, Config, ContractError};
+use crate::host::Host;
+use crate::storage;
+use crate::events;
+
+/// Highest platform fee the owner can set: 10%.
+pub const MAX_FEE_BPS: u16 = BPS_DENOMINATOR / 10;
+
+const ZERO_ADDRESS: Address = [0u8; 20];
+
+/// Stores the owner and the initial config. Called once, from the constructor.
+/// `input` is either empty (the deployer owns the contract and arbitrates, no fee, no limits)
+/// or the ABI-encoded (address owner, address arbitrator, uint16 feeBps, uint128 minJobBudget,
+/// uint32 maxMilestones). A zero owner means the deployer; a zero arbitrator means the owner.
+/// Input: deployer (Address), input (constructor call data)
+/// Output: 0 on success or error code
+pub fn init(host: &mut impl Host, deployer: Address, input: &[u8]) -> Result<(), ContractError> {
+    let mut owner = deployer;
+    let mut config = Config {
+        arbitrator: ZERO_ADDRESS,
+        fee_bps: 0,
+        min_job_budget: 0,
+        max_milestones: MAX_MILESTONES as u32,
+    };
+    if !input.is_empty() {
+        let mut input = Decoder::new(input);
+        let requested_owner = input.read::<Address>()?;
+        if requested_owner != ZERO_ADDRESS {
+            owner = requested_owner;
+        }
+        config.arbitrator = input.read::<Address>()?;
+        config.fee_bps = input.read::<u16>()?;
+        config.min_job_budget = input.read::<u128>()?;
+        config.max_milestones = input.read::<u32>()?;
+    }
+    if config.arbitrator == ZERO_ADDRESS {
+        config.arbitrator = owner;
+    }
+    check_config(&config)?;
+
+    storage::set_owner(host, &owner);
+    storage::save_config(host, &config);
+    events::ownership_transferred(host, &ZERO_ADDRESS, &owner);
+    events::config_updated(host, &config);
+    Ok(())
+}
+
+/// The current config. Falls back to the constructor defaults if none was ever stored.
+pub fn config(host: &impl Host) -> Config {
+    storage::load_config(host).unwrap_or(Config {
+        arbitrator: storage::owner(host),
+        fee_bps: 0,
+        min_job_budget: 0,
+        max_milestones: MAX_MILESTONES as u32,
+    })
+}
+
+/// Gets ownership and config.
+/// Output: (owner, pending_owner, arbitrator, fee_bps, min_job_budget, max_milestones)
+pub fn get_config(host: &impl Host) -> (Address, Address, Address, u16, Balance, u32) {
+    let config = config(host);
+    (
+        storage::owner(host),
+        storage::pending_owner(host),
+        config.arbitrator,
+        config.fee_bps,
+        config.min_job_budget,
+        config.max_milestones,
+    )
+}
+
+/// Replaces the config. Owner only.
+/// Input: caller (Address), config (Config)
+/// Output: 0 on success or error code
+pub fn set_config(host: &mut impl Host, caller: Address, config: Config) -> Result<(), ContractError> {
+    ensure_owner(host, &caller)?;
+    check_config(&config)?;
+    storage::save_config(host, &config);
+    events::config_updated(host, &config);
+    Ok(())
+}
+
+/// Starts handing the contract over to `new_owner`, who must then call accept_ownership.
+/// Passing the zero address cancels a pending transfer. Owner only.
+/// Input: caller (Address), new_owner (Address)
+/// Output: 0 on success or error code
+pub fn transfer_ownership(host: &mut impl Host, caller: Address, new_owner: Address) -> Result<(), ContractError> {
+    ensure_owner(host, &caller)?;
+    storage::set_pending_owner(host, &new_owner);
+    events::ownership_transfer_started(host, &caller, &new_owner);
+    Ok(())
+}
+
+/// Completes a transfer started by transfer_ownership. Only the pending owner can call it.
+/// Input: caller (Address)
+/// Output: 0 on success or error code
+pub fn accept_ownership(host: &mut impl Host, caller: Address) -> Result<(), ContractError> {
+    let pending_owner = storage::pending_owner(host);
+    if pending_owner == ZERO_ADDRESS || caller != pending_owner {
+        return Err(ContractError::Unauthorized(caller));
+    }
+    let previous_owner = storage::owner(host);
+    storage::set_owner(host, &caller);
+    storage::set_pending_owner(host, &ZERO_ADDRESS);
+    events::ownership_transferred(host, &previous_owner, &caller);
+    Ok(())
+}
+
+/// Pauses or unpauses the contract. Owner only.
+/// While paused every state-changing call fails with EnforcedPause, except the admin calls and
+/// the ways for a client to get their escrow back (cancelling or expiring an Open job).
+/// Input: caller (Address), paused (bool)
+/// Output: 0 on success or error code
+pub fn set_paused(host: &mut impl Host, caller: Address, paused: bool) -> Result<(), ContractError> {
+    ensure_owner(host, &caller)?;
+    if storage::is_paused(host) == paused {
+        return Err(ContractError::InvalidOperation); // Already in the requested state
+    }
+    storage::set_paused(host, paused);
+    events::pause_changed(host, &caller, paused);
+    Ok(())
+}
+
+/// Fails with EnforcedPause while the contract is paused.
+pub fn ensure_not_paused(host: &impl Host) -> Result<(), ContractError> {
+    if storage::is_paused(host) {
+        return Err(ContractError::EnforcedPause);
+    }
+    Ok(())
+}
+
+/// Sends `amount` of the platform fees collected in `token` to `to`. Owner only.
+/// Input: caller (Address), token (Address, escrow::NATIVE_TOKEN for the native token), to (Address), amount (Balance)
+/// Output: 0 on success or error code
+pub fn withdraw_fees(host: &mut impl Host, caller: Address, token: Address, to: Address, amount: Balance) -> Result<(), ContractError> {
+    ensure_owner(host, &caller)?;
+    escrow::withdraw_fees(host, &token, &to, amount)
+}
+
+/// Fails with Unauthorized unless `caller` is the contract owner.
+pub fn ensure_owner(host: &impl Host, caller: &Address) -> Result<(), ContractError> {
+    if *caller != storage::owner(host) {
+        return Err(ContractError::Unauthorized(*caller));
+    }
+    Ok(())
+}
+
+fn check_config(config: &Config) -> Result<(), ContractError> {
+    let valid = config.arbitrator != ZERO_ADDRESS // Disputes would become unresolvable
+        && config.fee_bps <= MAX_FEE_BPS
+        && (1..=MAX_MILESTONES as u32).contains(&config.max_milestones);
+    if !valid {
+        return Err(ContractError::InvalidInput);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::{Word, WORD};
+    use crate::agreement_management;
+    use crate::job_management;
+    use crate::mock::{job_params, MockHost};
+    use crate::proposal_management;
+    use crate::types::{AgreementStatus, JobParams, JobStatus, ProposalStatus};
+
+    const CLIENT: Address = [1u8; 20];
+    const FREELANCER: Address = [2u8; 20];
+    const ARBITRATOR: Address = [3u8; 20];
+    const OWNER: Address = [9u8; 20];
+    const STRANGER: Address = [7u8; 20];
+
+    fn deployed() -> MockHost {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        host
+    }
+
+    fn constructor_input(owner: Address, arbitrator: Address, fee_bps: u16, min_job_budget: Balance, max_milestones: u32) -> Vec<u8> {
+        let words = [
+            owner.to_word(),
+            arbitrator.to_word(),
+            fee_bps.to_word(),
+            min_job_budget.to_word(),
+            max_milestones.to_word(),
+        ];
+        words.concat()
+    }
+
+    #[test]
+    fn deploy_without_input_uses_defaults() {
+        let host = deployed();
+        assert_eq!(get_config(&host), (OWNER, ZERO_ADDRESS, OWNER, 0, 0, MAX_MILESTONES as u32));
+        assert_eq!(host.events.len(), 2); // OwnershipTransferred and ConfigUpdated
+    }
+
+    #[test]
+    fn deploy_reads_config_from_input() {
+        let mut host = MockHost::new();
+        let input = constructor_input(STRANGER, ARBITRATOR, 250, 100, 4);
+        assert!(!host.deploy_with(OWNER, &input).0);
+        assert_eq!(get_config(&host), (STRANGER, ZERO_ADDRESS, ARBITRATOR, 250, 100, 4));
+
+        // Zero owner and arbitrator fall back to the deployer
+        let mut host = MockHost::new();
+        host.deploy_with(OWNER, &constructor_input(ZERO_ADDRESS, ZERO_ADDRESS, 0, 0, 1));
+        assert_eq!(get_config(&host), (OWNER, ZERO_ADDRESS, OWNER, 0, 0, 1));
+    }
+
+    #[test]
+    fn deploy_rejects_invalid_config() {
+        for input in [
+            constructor_input(OWNER, ARBITRATOR, MAX_FEE_BPS + 1, 0, 4),
+            constructor_input(OWNER, ARBITRATOR, 0, 0, 0),
+            constructor_input(OWNER, ARBITRATOR, 0, 0, MAX_MILESTONES as u32 + 1),
+            constructor_input(OWNER, ARBITRATOR, 0, 0, 4)[..4 * WORD].to_vec(),
+        ] {
+            let mut host = MockHost::new();
+            let (reverted, data) = host.deploy_with(OWNER, &input);
+            assert!(reverted);
+            assert_eq!(data, crate::keccak::selector("InvalidInput()").to_be_bytes());
+            assert!(host.storage.is_empty());
+        }
+    }
+
+    #[test]
+    fn only_owner_sets_config() {
+        let mut host = deployed();
+        let new_config = Config { arbitrator: ARBITRATOR, fee_bps: 100, min_job_budget: 10, max_milestones: 2 };
+        assert_eq!(set_config(&mut host, STRANGER, new_config), Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(
+            set_config(&mut host, OWNER, Config { arbitrator: ZERO_ADDRESS, ..new_config }),
+            Err(ContractError::InvalidInput)
+        );
+
+        set_config(&mut host, OWNER, new_config).unwrap();
+        assert_eq!(config(&host), new_config);
+    }
+
+    #[test]
+    fn ownership_transfer_takes_two_steps() {
+        let mut host = deployed();
+        assert_eq!(transfer_ownership(&mut host, STRANGER, STRANGER), Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(accept_ownership(&mut host, STRANGER), Err(ContractError::Unauthorized(STRANGER)));
+
+        transfer_ownership(&mut host, OWNER, CLIENT).unwrap();
+        assert_eq!(storage::owner(&host), OWNER); // Nothing changes until CLIENT accepts
+        assert_eq!(accept_ownership(&mut host, STRANGER), Err(ContractError::Unauthorized(STRANGER)));
+
+        accept_ownership(&mut host, CLIENT).unwrap();
+        assert_eq!(get_config(&host).0, CLIENT);
+        assert_eq!(get_config(&host).1, ZERO_ADDRESS);
+        assert_eq!(ensure_owner(&host, &OWNER), Err(ContractError::Unauthorized(OWNER)));
+
+        // The zero address cancels a pending transfer
+        transfer_ownership(&mut host, CLIENT, STRANGER).unwrap();
+        transfer_ownership(&mut host, CLIENT, ZERO_ADDRESS).unwrap();
+        assert_eq!(accept_ownership(&mut host, STRANGER), Err(ContractError::Unauthorized(STRANGER)));
+    }
+
+    #[test]
+    fn only_owner_withdraws_fees() {
+        let mut host = deployed();
+        let native = escrow::NATIVE_TOKEN;
+        storage::set_treasury_balance(&mut host, &native, 100);
+        assert_eq!(withdraw_fees(&mut host, STRANGER, native, STRANGER, 100), Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(withdraw_fees(&mut host, OWNER, native, CLIENT, 101), Err(ContractError::InsufficientEscrow(100, 101)));
+
+        withdraw_fees(&mut host, OWNER, native, CLIENT, 60).unwrap();
+        assert_eq!(host.transferred_to(&CLIENT), 60);
+        assert_eq!(storage::treasury_balance(&host, &native), 40);
+        assert_eq!(host.events.last().unwrap().topics, vec![events::FEES_WITHDRAWN, native.to_word(), CLIENT.to_word()]);
+    }
+
+    #[test]
+    fn only_owner_pauses() {
+        let mut host = deployed();
+        assert_eq!(set_paused(&mut host, STRANGER, true), Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(set_paused(&mut host, OWNER, false), Err(ContractError::InvalidOperation));
+
+        set_paused(&mut host, OWNER, true).unwrap();
+        assert_eq!(ensure_not_paused(&host), Err(ContractError::EnforcedPause));
+        assert_eq!(host.events.last().unwrap().topics, vec![events::PAUSED]);
+        assert_eq!(set_paused(&mut host, OWNER, true), Err(ContractError::InvalidOperation));
+
+        set_paused(&mut host, OWNER, false).unwrap();
+        assert_eq!(ensure_not_paused(&host), Ok(()));
+    }
+
+    #[test]
+    fn pause_blocks_every_mutation_except_refunds() {
+        let mut host = deployed();
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 1_000).unwrap();
+        job_management::create_job(&mut host, CLIENT, &JobParams { bidding_deadline: 50, ..job_params(1_000) }, 400).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+        agreement_management::create_agreement_from_proposal(&mut host, CLIENT, 0, &[]).unwrap();
+        set_paused(&mut host, OWNER, true).unwrap();
+
+        assert_eq!(job_management::create_job(&mut host, CLIENT, &job_params(1_000), 0), Err(ContractError::EnforcedPause));
+        assert_eq!(job_management::fund_job(&mut host, CLIENT, 0, 10), Err(ContractError::EnforcedPause));
+        assert_eq!(job_management::update_job_status(&mut host, CLIENT, 0, JobStatus::Completed as u8), Err(ContractError::EnforcedPause));
+        assert_eq!(proposal_management::submit_proposal(&mut host, 1, FREELANCER, 300, 0, [0u8; 32]), Err(ContractError::EnforcedPause));
+        assert_eq!(proposal_management::update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Rejected as u8), Err(ContractError::EnforcedPause));
+        assert_eq!(agreement_management::submit_milestone(&mut host, FREELANCER, 0, 0), Err(ContractError::EnforcedPause));
+        assert_eq!(agreement_management::update_agreement_status(&mut host, CLIENT, 0, AgreementStatus::Disputed as u8), Err(ContractError::EnforcedPause));
+
+        // Open jobs can still be cancelled or expired, refunding the client
+        host.timestamp = 51 + job_management::BIDDING_GRACE_PERIOD;
+        job_management::expire_job(&mut host, 1).unwrap();
+        assert_eq!(host.transferred_to(&CLIENT), 400);
+    }
+
+    #[test]
+    fn config_limits_apply_to_jobs_and_schedules() {
+        let mut host = deployed();
+        let limits = Config { arbitrator: OWNER, fee_bps: 0, min_job_budget: 500, max_milestones: 2 };
+        set_config(&mut host, OWNER, limits).unwrap();
+
+        assert_eq!(job_management::create_job(&mut host, CLIENT, &job_params(499), 0), Err(ContractError::InvalidInput));
+        job_management::create_job(&mut host, CLIENT, &job_params(900), 900).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, FREELANCER, 900, 0, [0u8; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+
+        let schedule = [(300, [0u8; 32]); 3];
+        assert_eq!(
+            agreement_management::create_agreement_from_proposal(&mut host, CLIENT, 0, &schedule),
+            Err(ContractError::InvalidInput)
+        );
+        agreement_management::create_agreement_from_proposal(&mut host, CLIENT, 0, &[(450, [0u8; 32]); 2]).unwrap();
+    }
+}
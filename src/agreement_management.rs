@@ -1,187 +1,802 @@
 // filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/agreement_management.rs
-#![cfg_attr(not(feature = "std"), no_std)]
 
-use crate::types::{Agreement, AgreementId, AgreementStatus, JobId, UserId, Balance, ContractError};
+use crate::types::{
+    Agreement, AgreementId, AgreementStatus, JobId, Milestone, MilestoneIndex, MilestoneStatus, ProposalId,
+    ProposalStatus, Address, Balance, ContractError,
+};
 use crate::job_management; // To update job status
 use crate::proposal_management; // To get proposal details
-use uapi::{HostFn, HostFnImpl as api};
-
-const MAX_AGREEMENTS: usize = 100; // Example limit
-static mut AGREEMENTS: [Option<Agreement>; MAX_AGREEMENTS] = [None; MAX_AGREEMENTS];
-static mut NEXT_AGREEMENT_ID: AgreementId = 0;
-
-fn find_agreement_index(agreement_id: AgreementId) -> Option<usize> {
-    unsafe {
-        for i in 0..MAX_AGREEMENTS {
-            if let Some(agreement) = &AGREEMENTS[i] {
-                if agreement.id == agreement_id {
-                    return Some(i);
-                }
-            }
-        }
-        None
-    }
-}
+use crate::host::Host;
+use crate::storage;
+use crate::escrow;
+use crate::events;
+use crate::reputation;
+use crate::admin;
+use crate::pagination::{self, MAX_PAGE_SIZE};
+
+const MAX_AGREEMENTS: AgreementId = 100; // Example limit
+pub const MAX_MILESTONES: usize = 16; // Hard cap per agreement, bounds the schedule decoding buffer
 
 /// Creates an agreement when a proposal is accepted.
-/// Input: proposal_id (ProposalId)
+/// Only the job's client can do this, since it starts the job. Every other proposal still
+/// Submitted or Accepted for the job is rejected.
+/// The agreement is for the price the two sides settled on: the bid, or the last counter-offer
+/// (see proposal_management::get_negotiation).
+/// `schedule` lists (amount, description_hash) per milestone and must add up to that price,
+/// with at most `max_milestones` (see admin::Config) entries.
+/// An empty schedule means a single milestone for the whole price.
+/// Input: caller (Address), proposal_id (ProposalId), schedule (&[(Balance, [u8; 32])])
 /// Output: agreement_id (AgreementId) or error code
-pub fn create_agreement_from_proposal(proposal_id: ProposalId) -> Result<AgreementId, ContractError> {
-    unsafe {
-        // 1. Fetch proposal details
-        let (job_id, freelancer_id, bid_amount, proposal_status_u8) = 
-            proposal_management::get_proposal(proposal_id).map_err(|_| ContractError::NotFound)?;
-
-        if proposal_status_u8 != crate::types::ProposalStatus::Accepted as u8 {
-            return Err(ContractError::InvalidOperation); // Proposal not accepted
-        }
+pub fn create_agreement_from_proposal(
+    host: &mut impl Host,
+    caller: Address,
+    proposal_id: ProposalId,
+    schedule: &[(Balance, [u8; 32])],
+) -> Result<AgreementId, ContractError> {
+    admin::ensure_not_paused(host)?;
+    // 1. Fetch proposal details
+    let (job_id, freelancer_id, _, proposal_status_u8, ..) =
+        proposal_management::get_proposal(host, proposal_id)?;
+    let (_, agreed_amount) = proposal_management::get_negotiation(host, proposal_id)?;
 
-        // 2. Fetch job details to get client_id
-        let (client_id, _, job_status_u8) = 
-            job_management::get_job(job_id).map_err(|_| ContractError::NotFound)?;
-        
-        if job_status_u8 != crate::types::JobStatus::Open as u8 {
-             // Or if it was already in progress with another freelancer, depends on logic
-            return Err(ContractError::InvalidOperation); 
-        }
+    if proposal_status_u8 != ProposalStatus::Accepted as u8 {
+        return Err(ContractError::InvalidOperation); // Proposal not accepted
+    }
 
-        if NEXT_AGREEMENT_ID >= MAX_AGREEMENTS as AgreementId {
-            return Err(ContractError::StorageFull);
-        }
+    // 2. Fetch job details to get client_id
+    let (client_id, _, job_status_u8, ..) =
+        job_management::get_job(host, job_id)?;
 
-        let agreement_id = NEXT_AGREEMENT_ID;
-        let new_agreement = Agreement {
-            id: agreement_id,
-            job_id,
-            client_id,
-            freelancer_id,
-            total_amount: bid_amount, // Or could be job_budget if fixed price
-            status: AgreementStatus::Active,
+    if job_status_u8 != crate::types::JobStatus::Open as u8 {
+         // Or if it was already in progress with another freelancer, depends on logic
+        return Err(ContractError::InvalidOperation);
+    }
+
+    if caller != client_id {
+        return Err(ContractError::Unauthorized(caller));
+    }
+
+    let whole_amount = [(agreed_amount, [0u8; 32])];
+    let schedule = if schedule.is_empty() { &whole_amount[..] } else { schedule };
+    check_schedule(schedule, agreed_amount, admin::config(host).max_milestones)?;
+
+    // The freelancer only starts once the agreed amount is actually escrowed
+    let escrowed = storage::escrow_balance(host, job_id);
+    if escrowed < agreed_amount {
+        return Err(ContractError::InsufficientEscrow(escrowed, agreed_amount));
+    }
+
+    let agreement_id = storage::next_agreement_id(host);
+    if agreement_id >= MAX_AGREEMENTS {
+        return Err(ContractError::StorageFull);
+    }
+
+    let new_agreement = Agreement {
+        id: agreement_id,
+        job_id,
+        client_id,
+        freelancer_id,
+        total_amount: agreed_amount,
+        status: AgreementStatus::Active,
+        milestone_count: schedule.len() as u32,
+        fee_bps: admin::config(host).fee_bps,
+    };
+    storage::save_agreement(host, &new_agreement);
+    for (index, (amount, description_hash)) in schedule.iter().enumerate() {
+        let milestone = Milestone {
+            agreement_id,
+            index: index as MilestoneIndex,
+            amount: *amount,
+            description_hash: *description_hash,
+            status: MilestoneStatus::Pending,
         };
+        storage::save_milestone(host, &milestone);
+    }
+    storage::set_next_agreement_id(host, agreement_id + 1);
+    events::agreement_created(host, agreement_id, job_id, &client_id, &freelancer_id, agreed_amount);
 
-        let mut stored = false;
-        for i in 0..MAX_AGREEMENTS {
-            if AGREEMENTS[i].is_none() {
-                AGREEMENTS[i] = Some(new_agreement);
-                stored = true;
-                break;
-            }
-        }
-        if !stored {
-            return Err(ContractError::StorageFull);
-        }
-        
-        NEXT_AGREEMENT_ID += 1;
+    // 3. Update job status to InProgress
+    job_management::set_job_status(host, job_id, crate::types::JobStatus::InProgress)?;
+    proposal_management::reject_competing_proposals(host, job_id, proposal_id);
 
-        // 3. Update job status to InProgress
-        job_management::update_job_status(job_id, crate::types::JobStatus::InProgress as u8)?;
+    Ok(agreement_id)
+}
+
+/// Accepts a proposal and starts the work in one call: the proposal becomes Accepted, any value
+/// sent along is added to the escrow, and the agreement is created, which moves the job to
+/// InProgress and rejects the job's other proposals.
+/// A proposal the freelancer already accepted (their answer to the client's counter-offer)
+/// is taken as it is.
+/// Client only. If any step fails the whole call reverts.
+/// Input: caller (Address), proposal_id (ProposalId), schedule (see create_agreement_from_proposal),
+///        deposit (Balance, value transferred)
+/// Output: agreement_id (AgreementId) or error code
+pub fn accept_proposal(
+    host: &mut impl Host,
+    caller: Address,
+    proposal_id: ProposalId,
+    schedule: &[(Balance, [u8; 32])],
+    deposit: Balance,
+) -> Result<AgreementId, ContractError> {
+    let (job_id, _, _, proposal_status_u8, ..) = proposal_management::get_proposal(host, proposal_id)?;
+    let (client_id, ..) = job_management::get_job(host, job_id)?;
+    if caller != client_id {
+        return Err(ContractError::Unauthorized(caller));
+    }
 
-        Ok(agreement_id)
+    if proposal_status_u8 != ProposalStatus::Accepted as u8 {
+        proposal_management::update_proposal_status(host, caller, proposal_id, ProposalStatus::Accepted as u8)?;
+    }
+    if deposit != 0 {
+        job_management::fund_job(host, caller, job_id, deposit)?;
     }
+    create_agreement_from_proposal(host, caller, proposal_id, schedule)
+}
+
+/// A schedule has 1..=max_milestones non-zero amounts that add up exactly to `total`.
+fn check_schedule(schedule: &[(Balance, [u8; 32])], total: Balance, max_milestones: u32) -> Result<(), ContractError> {
+    if schedule.is_empty() || schedule.len() > max_milestones as usize {
+        return Err(ContractError::InvalidInput);
+    }
+    let mut sum: Balance = 0;
+    for (amount, _) in schedule {
+        if *amount == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+        sum = sum.checked_add(*amount).ok_or(ContractError::InvalidInput)?;
+    }
+    if sum != total {
+        return Err(ContractError::InvalidInput);
+    }
+    Ok(())
 }
 
 /// Gets agreement details.
 /// Input: agreement_id (AgreementId)
-/// Output: (job_id, client_id, freelancer_id, total_amount, status_u8) or error code
-pub fn get_agreement(agreement_id: AgreementId) -> Result<(JobId, UserId, UserId, Balance, u8), ContractError> {
-    unsafe {
-        if let Some(index) = find_agreement_index(agreement_id) {
-            if let Some(agreement) = &AGREEMENTS[index] {
-                return Ok((
-                    agreement.job_id,
-                    agreement.client_id,
-                    agreement.freelancer_id,
-                    agreement.total_amount,
-                    agreement.status as u8,
-                ));
-            }
+/// Output: (job_id, client_id, freelancer_id, total_amount, status_u8, milestone_count) or error code
+pub fn get_agreement(host: &impl Host, agreement_id: AgreementId) -> Result<(JobId, Address, Address, Balance, u8, u32), ContractError> {
+    let agreement = storage::load_agreement(host, agreement_id).ok_or(ContractError::NotFound(agreement_id))?;
+    Ok((
+        agreement.job_id,
+        agreement.client_id,
+        agreement.freelancer_id,
+        agreement.total_amount,
+        agreement.status as u8,
+        agreement.milestone_count,
+    ))
+}
+
+/// Gets how the agreement pays out: the gross amount, the platform fee fixed when it was
+/// made, and what reaches the freelancer once every milestone is released. The fee is taken
+/// per milestone, so it is the sum of the per-milestone fees.
+/// platform_fee and freelancer_amount are a projection of the full schedule, so they only hold
+/// for Active and Completed agreements. A Resolved agreement paid out released_amount through
+/// milestones and then the split reported by the DisputeResolved event.
+/// Input: agreement_id (AgreementId)
+/// Output: (total_amount, fee_bps, platform_fee, freelancer_amount, released_amount) or error code
+pub fn get_payout_breakdown(host: &impl Host, agreement_id: AgreementId) -> Result<(Balance, u16, Balance, Balance, Balance), ContractError> {
+    let agreement = storage::load_agreement(host, agreement_id).ok_or(ContractError::NotFound(agreement_id))?;
+    let mut platform_fee: Balance = 0;
+    let mut released: Balance = 0;
+    for index in 0..agreement.milestone_count {
+        let milestone = load_milestone(host, &agreement, index)?;
+        platform_fee += escrow::share_of(milestone.amount, agreement.fee_bps);
+        if milestone.status == MilestoneStatus::Paid {
+            released += milestone.amount;
         }
-        Err(ContractError::NotFound)
     }
+    Ok((agreement.total_amount, agreement.fee_bps, platform_fee, agreement.total_amount - platform_fee, released))
 }
 
-/// Updates an agreement's status (e.g., complete, dispute).
-/// Input: agreement_id (AgreementId), new_status_u8 (u8)
+/// Lists the ids of the agreements `user` is a party to, as client or freelancer.
+/// Input: user (Address), offset (u32), limit (u32), page buffer
+/// Output: (total agreements for the user, number of ids written to `page`)
+pub fn list_agreements_for_user(host: &impl Host, user: &Address, offset: u32, limit: u32, page: &mut [AgreementId; MAX_PAGE_SIZE]) -> (u32, usize) {
+    let matches = (0..storage::next_agreement_id(host)).filter(|agreement_id| {
+        matches!(
+            storage::load_agreement(host, *agreement_id),
+            Some(agreement) if agreement.client_id == *user || agreement.freelancer_id == *user
+        )
+    });
+    pagination::paginate(matches, offset, limit, page)
+}
+
+/// Updates an agreement's status (e.g. dispute) on behalf of `caller`.
+/// Completion is not set here: an agreement completes when its last milestone is released.
+/// Input: caller (Address), agreement_id (AgreementId), new_status_u8 (u8)
 /// Output: 0 on success or error code
-pub fn update_agreement_status(agreement_id: AgreementId, new_status_u8: u8) -> Result<(), ContractError> {
-    unsafe {
-        let new_status = AgreementStatus::from_u8(new_status_u8).ok_or(ContractError::InvalidInput)?;
-
-        if let Some(index) = find_agreement_index(agreement_id) {
-            if let Some(agreement) = &mut AGREEMENTS[index] {
-                // Basic state transition validation
-                match (agreement.status, new_status) {
-                    (AgreementStatus::Active, AgreementStatus::Completed) => {
-                        // When agreement completes, update the job status as well
-                        job_management::update_job_status(agreement.job_id, crate::types::JobStatus::Completed as u8)?;
-                    },
-                    (AgreementStatus::Active, AgreementStatus::Disputed) => {},
-                    // Add more transitions as needed
-                    _ => return Err(ContractError::InvalidOperation),
-                }
-                agreement.status = new_status;
-                return Ok(());
-            }
+pub fn update_agreement_status(host: &mut impl Host, caller: Address, agreement_id: AgreementId, new_status_u8: u8) -> Result<(), ContractError> {
+    admin::ensure_not_paused(host)?;
+    let new_status = AgreementStatus::from_u8(new_status_u8).ok_or(ContractError::InvalidInput)?;
+    let mut agreement = storage::load_agreement(host, agreement_id).ok_or(ContractError::NotFound(agreement_id))?;
+
+    // Basic state transition validation
+    match (agreement.status, new_status) {
+        (AgreementStatus::Active, AgreementStatus::Completed) => {
+            return Err(ContractError::InvalidOperation); // Use releaseMilestone
         }
-        Err(ContractError::NotFound)
+        (AgreementStatus::Active, AgreementStatus::Disputed) => {},
+        // Disputed -> Resolved only happens through resolve_dispute
+        (from, to) => return Err(ContractError::InvalidTransition(from as u8, to as u8)),
+    }
+    ensure_can_update_agreement(&agreement, &caller, new_status)?;
+
+    let old_status = agreement.status;
+    agreement.status = new_status;
+    storage::save_agreement(host, &agreement);
+    events::agreement_status_changed(host, agreement_id, old_status as u8, new_status as u8);
+    if new_status == AgreementStatus::Disputed {
+        reputation::record_dispute(host, &agreement);
     }
+    Ok(())
 }
 
-// --- ABI Helper Functions (Simplified) ---
+/// Checks that `caller` may move `agreement` to `new_status`.
+/// - Disputed: either party can raise a dispute.
+///
+/// There is no owner override: a dispute freezes the milestones and counts against both
+/// parties' reputation, so only the parties themselves may raise one.
+fn ensure_can_update_agreement(agreement: &Agreement, caller: &Address, new_status: AgreementStatus) -> Result<(), ContractError> {
+    let is_client = *caller == agreement.client_id;
+    let is_freelancer = *caller == agreement.freelancer_id;
+    let allowed = match new_status {
+        AgreementStatus::Disputed => is_client || is_freelancer,
+        _ => false,
+    };
+    if !allowed {
+        return Err(ContractError::Unauthorized(*caller));
+    }
+    Ok(())
+}
 
-/// Decodes create_agreement_from_proposal arguments: proposal_id (u32)
-pub fn decode_create_agreement_args(data: &[u8]) -> Result<ProposalId, ContractError> {
-    if data.len() < 4 { // selector + proposal_id
-        return Err(ContractError::InvalidInput);
+// --- Milestones ---
+
+/// Gets one milestone of an agreement.
+/// Input: agreement_id (AgreementId), index (MilestoneIndex)
+/// Output: (amount, description_hash, status_u8) or error code
+pub fn get_milestone(host: &impl Host, agreement_id: AgreementId, index: MilestoneIndex) -> Result<(Balance, [u8; 32], u8), ContractError> {
+    let agreement = storage::load_agreement(host, agreement_id).ok_or(ContractError::NotFound(agreement_id))?;
+    let milestone = load_milestone(host, &agreement, index)?;
+    Ok((milestone.amount, milestone.description_hash, milestone.status as u8))
+}
+
+/// The freelancer marks a milestone as delivered.
+/// Input: caller (Address), agreement_id (AgreementId), index (MilestoneIndex)
+/// Output: 0 on success or error code
+pub fn submit_milestone(host: &mut impl Host, caller: Address, agreement_id: AgreementId, index: MilestoneIndex) -> Result<(), ContractError> {
+    update_milestone_status(host, caller, agreement_id, index, MilestoneStatus::Submitted)?;
+    Ok(())
+}
+
+/// The client accepts a delivered milestone.
+/// Input: caller (Address), agreement_id (AgreementId), index (MilestoneIndex)
+/// Output: 0 on success or error code
+pub fn approve_milestone(host: &mut impl Host, caller: Address, agreement_id: AgreementId, index: MilestoneIndex) -> Result<(), ContractError> {
+    update_milestone_status(host, caller, agreement_id, index, MilestoneStatus::Approved)?;
+    Ok(())
+}
+
+/// The client pays out an approved milestone from escrow.
+/// Releasing the last unpaid milestone completes the agreement and the job,
+/// and refunds whatever is left in escrow to the client.
+/// Input: caller (Address), agreement_id (AgreementId), index (MilestoneIndex)
+/// Output: 0 on success or error code
+pub fn release_milestone(host: &mut impl Host, caller: Address, agreement_id: AgreementId, index: MilestoneIndex) -> Result<(), ContractError> {
+    let (mut agreement, milestone) = update_milestone_status(host, caller, agreement_id, index, MilestoneStatus::Paid)?;
+    escrow::release_with_fee(host, agreement.job_id, &agreement.freelancer_id, milestone.amount, agreement.fee_bps)?;
+
+    if all_milestones_paid(host, &agreement) {
+        agreement.status = AgreementStatus::Completed;
+        storage::save_agreement(host, &agreement);
+        events::agreement_status_changed(host, agreement_id, AgreementStatus::Active as u8, AgreementStatus::Completed as u8);
+        reputation::record_completion(host, &agreement);
+
+        // When agreement completes, update the job status as well
+        job_management::set_job_status(host, agreement.job_id, crate::types::JobStatus::Completed)?;
+        // Return any unused budget to the client
+        escrow::refund(host, agreement.job_id, &agreement.client_id)?;
     }
-    Ok(u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?))
+    Ok(())
 }
 
-/// Decodes get_agreement arguments: agreement_id (u32)
-pub fn decode_get_agreement_args(data: &[u8]) -> Result<AgreementId, ContractError> {
-    if data.len() < 4 { // selector + agreement_id
-        return Err(ContractError::InvalidInput);
+fn load_milestone(host: &impl Host, agreement: &Agreement, index: MilestoneIndex) -> Result<Milestone, ContractError> {
+    if index >= agreement.milestone_count {
+        return Err(ContractError::NotFound(index));
     }
-    Ok(u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?))
+    storage::load_milestone(host, agreement.id, index).ok_or(ContractError::NotFound(index))
 }
 
-/// Decodes update_agreement_status arguments: agreement_id (u32), new_status (u8)
-pub fn decode_update_agreement_status_args(data: &[u8]) -> Result<(AgreementId, u8), ContractError> {
-    if data.len() < 4 + 1 { // selector + agreement_id + status
+/// Moves a milestone of an Active agreement one step along Pending -> Submitted -> Approved -> Paid.
+/// Returns the agreement and the updated milestone.
+fn update_milestone_status(
+    host: &mut impl Host,
+    caller: Address,
+    agreement_id: AgreementId,
+    index: MilestoneIndex,
+    new_status: MilestoneStatus,
+) -> Result<(Agreement, Milestone), ContractError> {
+    admin::ensure_not_paused(host)?;
+    let agreement = storage::load_agreement(host, agreement_id).ok_or(ContractError::NotFound(agreement_id))?;
+    if agreement.status != AgreementStatus::Active {
+        return Err(ContractError::InvalidOperation); // Completed, or frozen by a dispute
+    }
+    let mut milestone = load_milestone(host, &agreement, index)?;
+
+    match (milestone.status, new_status) {
+        (MilestoneStatus::Pending, MilestoneStatus::Submitted) => {},
+        (MilestoneStatus::Submitted, MilestoneStatus::Approved) => {},
+        (MilestoneStatus::Approved, MilestoneStatus::Paid) => {},
+        (from, to) => return Err(ContractError::InvalidTransition(from as u8, to as u8)),
+    }
+    ensure_can_update_milestone(&agreement, &caller, new_status)?;
+
+    let old_status = milestone.status;
+    milestone.status = new_status;
+    storage::save_milestone(host, &milestone);
+    events::milestone_status_changed(host, agreement_id, index, old_status as u8, new_status as u8);
+    Ok((agreement, milestone))
+}
+
+/// Checks that `caller` may move a milestone of `agreement` to `new_status`.
+/// - Submitted: only the freelancer delivers work.
+/// - Approved, Paid: the client signs off on and pays for the delivered work.
+///
+/// There is no owner override: paying out spends the client's escrow, so a contested
+/// milestone is settled by the arbitrator through resolve_dispute.
+fn ensure_can_update_milestone(agreement: &Agreement, caller: &Address, new_status: MilestoneStatus) -> Result<(), ContractError> {
+    let allowed = match new_status {
+        MilestoneStatus::Submitted => *caller == agreement.freelancer_id,
+        MilestoneStatus::Approved | MilestoneStatus::Paid => *caller == agreement.client_id,
+        MilestoneStatus::Pending => false,
+    };
+    if !allowed {
+        return Err(ContractError::Unauthorized(*caller));
+    }
+    Ok(())
+}
+
+fn all_milestones_paid(host: &impl Host, agreement: &Agreement) -> bool {
+    (0..agreement.milestone_count).all(|index| {
+        matches!(storage::load_milestone(host, agreement.id, index), Some(m) if m.status == MilestoneStatus::Paid)
+    })
+}
+
+fn any_milestone_paid(host: &impl Host, agreement: &Agreement) -> bool {
+    (0..agreement.milestone_count).any(|index| {
+        matches!(storage::load_milestone(host, agreement.id, index), Some(m) if m.status == MilestoneStatus::Paid)
+    })
+}
+
+/// Sum of the milestones that have not been paid out yet, i.e. what is still at stake.
+fn unpaid_amount(host: &impl Host, agreement: &Agreement) -> Balance {
+    (0..agreement.milestone_count)
+        .filter_map(|index| storage::load_milestone(host, agreement.id, index))
+        .filter(|m| m.status != MilestoneStatus::Paid)
+        .map(|m| m.amount)
+        .sum()
+}
+
+// --- Disputes ---
+
+/// The arbitrator settles a Disputed agreement.
+/// The unpaid milestone amounts are split: `client_share_bps` (out of 10_000) goes back to
+/// the client together with any unused budget, the rest goes to the freelancer.
+/// The job ends up Completed if the freelancer was ever paid for it, through a released
+/// milestone or this split, and Cancelled otherwise.
+/// Input: caller (Address), agreement_id (AgreementId), client_share_bps (u16)
+/// Output: 0 on success or error code
+pub fn resolve_dispute(host: &mut impl Host, caller: Address, agreement_id: AgreementId, client_share_bps: u16) -> Result<(), ContractError> {
+    admin::ensure_not_paused(host)?;
+    if client_share_bps > escrow::BPS_DENOMINATOR {
         return Err(ContractError::InvalidInput);
     }
-    let agreement_id = u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?);
-    let status = data[4];
-    Ok((agreement_id, status))
+    let mut agreement = storage::load_agreement(host, agreement_id).ok_or(ContractError::NotFound(agreement_id))?;
+    if agreement.status != AgreementStatus::Disputed {
+        return Err(ContractError::InvalidTransition(agreement.status as u8, AgreementStatus::Resolved as u8));
+    }
+    if caller != admin::config(host).arbitrator {
+        return Err(ContractError::Unauthorized(caller));
+    }
+
+    let at_stake = unpaid_amount(host, &agreement);
+    let freelancer_amount = escrow::share_of(at_stake, escrow::BPS_DENOMINATOR - client_share_bps);
+    let client_amount = at_stake - freelancer_amount;
+
+    agreement.status = AgreementStatus::Resolved;
+    storage::save_agreement(host, &agreement);
+    events::agreement_status_changed(host, agreement_id, AgreementStatus::Disputed as u8, AgreementStatus::Resolved as u8);
+    events::dispute_resolved(host, agreement_id, client_share_bps, client_amount, freelancer_amount);
+
+    let job_status = if freelancer_amount > 0 || any_milestone_paid(host, &agreement) {
+        crate::types::JobStatus::Completed
+    } else {
+        crate::types::JobStatus::Cancelled
+    };
+    job_management::set_job_status(host, agreement.job_id, job_status)?;
+
+    escrow::release_with_fee(host, agreement.job_id, &agreement.freelancer_id, freelancer_amount, agreement.fee_bps)?;
+    escrow::refund(host, agreement.job_id, &agreement.client_id)?;
+    Ok(())
 }
 
-/// Encodes an AgreementId result
-pub fn encode_agreement_id_result(result: Result<AgreementId, ContractError>, output_buffer: &mut [u8; 32]) {
-    match result {
-        Ok(id) => {
-            output_buffer[28..].copy_from_slice(&id.to_be_bytes());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::Word;
+    use crate::mock::{job_params, MockHost};
+    use crate::types::{Config, JobStatus};
+
+    const CLIENT: Address = [1u8; 20];
+    const FREELANCER: Address = [2u8; 20];
+    const OWNER: Address = [9u8; 20];
+    const STRANGER: Address = [7u8; 20];
+
+    /// Job 0 with `escrowed` locked, and proposal 0 from FREELANCER bidding `bid`, accepted.
+    fn with_accepted_proposal(escrowed: Balance, bid: Balance) -> MockHost {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), escrowed).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, FREELANCER, bid, 0, [0u8; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+        host
+    }
+
+    fn agreement() -> Agreement {
+        Agreement {
+            id: 0,
+            job_id: 0,
+            client_id: CLIENT,
+            freelancer_id: FREELANCER,
+            total_amount: 500,
+            status: AgreementStatus::Active,
+            milestone_count: 1,
+            fee_bps: 0,
         }
-        Err(err) => {
-            output_buffer[31] = err as u8;
+    }
+
+    fn allowed(caller: Address, new_status: AgreementStatus) -> bool {
+        ensure_can_update_agreement(&agreement(), &caller, new_status).is_ok()
+    }
+
+    fn allowed_milestone(caller: Address, new_status: MilestoneStatus) -> bool {
+        ensure_can_update_milestone(&agreement(), &caller, new_status).is_ok()
+    }
+
+    /// Agreement 0 over an 800 bid paid in two milestones of 300 and 500, with 1_000 escrowed.
+    fn with_two_milestones() -> MockHost {
+        let mut host = with_accepted_proposal(1_000, 800);
+        create_agreement_from_proposal(&mut host, CLIENT, 0, &[(300, [0xaa; 32]), (500, [0xbb; 32])]).unwrap();
+        host
+    }
+
+    fn deliver(host: &mut MockHost, index: MilestoneIndex) {
+        submit_milestone(host, FREELANCER, 0, index).unwrap();
+        approve_milestone(host, CLIENT, 0, index).unwrap();
+    }
+
+    #[test]
+    fn only_freelancer_submits_milestones() {
+        assert!(allowed_milestone(FREELANCER, MilestoneStatus::Submitted));
+        assert!(!allowed_milestone(CLIENT, MilestoneStatus::Submitted));
+        assert!(!allowed_milestone(OWNER, MilestoneStatus::Submitted));
+    }
+
+    #[test]
+    fn only_client_approves_and_pays_milestones() {
+        for status in [MilestoneStatus::Approved, MilestoneStatus::Paid] {
+            assert!(allowed_milestone(CLIENT, status));
+            assert!(!allowed_milestone(FREELANCER, status));
+            assert!(!allowed_milestone(STRANGER, status));
+            assert!(!allowed_milestone(OWNER, status));
         }
     }
-}
 
-/// Encodes a GetAgreement result
-pub fn encode_get_agreement_result(result: Result<(JobId, UserId, UserId, Balance, u8), ContractError>, output_buffer: &mut [u8; 32]) {
-    match result {
-        Ok((job_id, client_id, freelancer_id, total_amount, status_u8)) => {
-            let mut temp_buf = [0u8; 4 + 4 + 4 + 16 + 1];
-            temp_buf[0..4].copy_from_slice(&job_id.to_be_bytes());
-            temp_buf[4..8].copy_from_slice(&client_id.to_be_bytes());
-            temp_buf[8..12].copy_from_slice(&freelancer_id.to_be_bytes());
-            temp_buf[12..28].copy_from_slice(&total_amount.to_be_bytes());
-            temp_buf[28] = status_u8;
-            let offset = 32 - temp_buf.len();
-            output_buffer[offset..].copy_from_slice(&temp_buf);
+    #[test]
+    fn either_party_can_raise_dispute() {
+        assert!(allowed(CLIENT, AgreementStatus::Disputed));
+        assert!(allowed(FREELANCER, AgreementStatus::Disputed));
+        assert!(!allowed(STRANGER, AgreementStatus::Disputed));
+        assert!(!allowed(OWNER, AgreementStatus::Disputed));
+    }
+
+    #[test]
+    fn agreement_starts_the_job() {
+        let mut host = with_accepted_proposal(1_000, 800);
+        assert_eq!(create_agreement_from_proposal(&mut host, CLIENT, 0, &[]), Ok(0));
+
+        assert_eq!(get_agreement(&host, 0), Ok((0, CLIENT, FREELANCER, 800, AgreementStatus::Active as u8, 1)));
+        assert_eq!(get_milestone(&host, 0, 0), Ok((800, [0u8; 32], MilestoneStatus::Pending as u8)));
+        assert_eq!(job_management::get_job(&host, 0).unwrap().2, JobStatus::InProgress as u8);
+        assert!(host.events.iter().any(|e| e.topics[0] == events::AGREEMENT_CREATED));
+    }
+
+    #[test]
+    fn agreement_requires_accepted_proposal_and_client() {
+        let mut host = with_accepted_proposal(1_000, 800);
+        proposal_management::submit_proposal(&mut host, 0, STRANGER, 700, 0, [0u8; 32]).unwrap();
+
+        assert_eq!(create_agreement_from_proposal(&mut host, CLIENT, 1, &[]), Err(ContractError::InvalidOperation));
+        assert_eq!(create_agreement_from_proposal(&mut host, FREELANCER, 0, &[]), Err(ContractError::Unauthorized(FREELANCER)));
+        assert_eq!(create_agreement_from_proposal(&mut host, CLIENT, 9, &[]), Err(ContractError::NotFound(9)));
+    }
+
+    #[test]
+    fn agreement_requires_escrowed_bid() {
+        let mut host = with_accepted_proposal(500, 800);
+        assert_eq!(create_agreement_from_proposal(&mut host, CLIENT, 0, &[]), Err(ContractError::InsufficientEscrow(500, 800)));
+
+        job_management::fund_job(&mut host, CLIENT, 0, 300).unwrap();
+        assert_eq!(create_agreement_from_proposal(&mut host, CLIENT, 0, &[]), Ok(0));
+    }
+
+    #[test]
+    fn agreement_is_for_the_negotiated_amount() {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 1_000).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, FREELANCER, 900, 0, [0u8; 32]).unwrap();
+        proposal_management::counter_offer(&mut host, CLIENT, 0, 600, [0xaa; 32]).unwrap();
+        proposal_management::counter_offer(&mut host, FREELANCER, 0, 700, [0xbb; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+
+        let hash = [0u8; 32];
+        let result = create_agreement_from_proposal(&mut host, CLIENT, 0, &[(450, hash), (450, hash)]);
+        assert_eq!(result, Err(ContractError::InvalidInput)); // Adds up to the bid, not the price
+        create_agreement_from_proposal(&mut host, CLIENT, 0, &[]).unwrap();
+        assert_eq!(get_agreement(&host, 0).unwrap().3, 700);
+    }
+
+    #[test]
+    fn accept_proposal_starts_the_work_in_one_call() {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 500).unwrap();
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 0).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, STRANGER, 700, 0, [0u8; 32]).unwrap();
+        proposal_management::submit_proposal(&mut host, 1, STRANGER, 700, 0, [0u8; 32]).unwrap();
+
+        assert_eq!(accept_proposal(&mut host, STRANGER, 0, &[], 300), Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(accept_proposal(&mut host, CLIENT, 0, &[], 300), Ok(0));
+
+        let status = |host: &MockHost, id| proposal_management::get_proposal(host, id).unwrap().3;
+        assert_eq!(status(&host, 0), ProposalStatus::Accepted as u8);
+        assert_eq!(status(&host, 1), ProposalStatus::Rejected as u8);
+        assert_eq!(status(&host, 2), ProposalStatus::Submitted as u8); // Other job
+        assert_eq!(job_management::get_escrow_balance(&host, 0), Ok(800));
+        assert_eq!(job_management::get_job(&host, 0).unwrap().2, JobStatus::InProgress as u8);
+        assert_eq!(get_agreement(&host, 0).unwrap().2, FREELANCER);
+    }
+
+    #[test]
+    fn accept_proposal_takes_a_negotiated_deal() {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 0).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, FREELANCER, 900, 0, [0u8; 32]).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, STRANGER, 700, 0, [0u8; 32]).unwrap();
+        proposal_management::counter_offer(&mut host, CLIENT, 0, 750, [0u8; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, FREELANCER, 0, ProposalStatus::Accepted as u8).unwrap();
+
+        assert_eq!(accept_proposal(&mut host, CLIENT, 0, &[], 750), Ok(0));
+        assert_eq!(get_agreement(&host, 0).unwrap().3, 750);
+        assert_eq!(proposal_management::get_proposal(&host, 1).unwrap().3, ProposalStatus::Rejected as u8);
+        assert_eq!(job_management::get_job(&host, 0).unwrap().2, JobStatus::InProgress as u8);
+    }
+
+    #[test]
+    fn agreement_rejects_rival_proposals() {
+        let mut host = with_accepted_proposal(1_000, 800);
+        proposal_management::submit_proposal(&mut host, 0, STRANGER, 700, 0, [0u8; 32]).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, OWNER, 600, 0, [0u8; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, CLIENT, 1, ProposalStatus::Accepted as u8).unwrap();
+
+        create_agreement_from_proposal(&mut host, CLIENT, 0, &[]).unwrap();
+        let status = |host: &MockHost, id| proposal_management::get_proposal(host, id).unwrap().3;
+        assert_eq!(status(&host, 1), ProposalStatus::Rejected as u8);
+        assert_eq!(status(&host, 2), ProposalStatus::Rejected as u8);
+    }
+
+    #[test]
+    fn schedule_must_add_up_to_bid() {
+        let mut host = with_accepted_proposal(1_000, 800);
+        let hash = [0u8; 32];
+        let too_many = [(50, hash); MAX_MILESTONES + 1];
+
+        for schedule in [&[(300, hash), (400, hash)][..], &[(800, hash), (0, hash)], &[(u128::MAX, hash), (801, hash)], &too_many] {
+            assert_eq!(create_agreement_from_proposal(&mut host, CLIENT, 0, schedule), Err(ContractError::InvalidInput));
         }
-        Err(err) => {
-            output_buffer[31] = err as u8;
+        assert!(storage::load_agreement(&host, 0).is_none());
+    }
+
+    #[test]
+    fn milestones_are_stored_in_order() {
+        let host = with_two_milestones();
+        assert_eq!(get_agreement(&host, 0).unwrap().5, 2);
+        assert_eq!(get_milestone(&host, 0, 0), Ok((300, [0xaa; 32], MilestoneStatus::Pending as u8)));
+        assert_eq!(get_milestone(&host, 0, 1), Ok((500, [0xbb; 32], MilestoneStatus::Pending as u8)));
+        assert_eq!(get_milestone(&host, 0, 2), Err(ContractError::NotFound(2)));
+    }
+
+    #[test]
+    fn milestones_follow_submit_approve_release() {
+        let mut host = with_two_milestones();
+        assert_eq!(approve_milestone(&mut host, CLIENT, 0, 0), Err(ContractError::InvalidTransition(0, 2)));
+        submit_milestone(&mut host, FREELANCER, 0, 0).unwrap();
+        assert_eq!(release_milestone(&mut host, CLIENT, 0, 0), Err(ContractError::InvalidTransition(1, 3)));
+        approve_milestone(&mut host, CLIENT, 0, 0).unwrap();
+        release_milestone(&mut host, CLIENT, 0, 0).unwrap();
+
+        assert_eq!(get_milestone(&host, 0, 0).unwrap().2, MilestoneStatus::Paid as u8);
+        assert_eq!(host.transfers, vec![(FREELANCER, 300)]);
+        assert_eq!(storage::escrow_balance(&host, 0), 700);
+        assert_eq!(get_agreement(&host, 0).unwrap().4, AgreementStatus::Active as u8);
+        assert_eq!(release_milestone(&mut host, CLIENT, 0, 0), Err(ContractError::InvalidTransition(3, 3)));
+    }
+
+    #[test]
+    fn last_release_completes_agreement_and_refunds_client() {
+        let mut host = with_two_milestones();
+        deliver(&mut host, 1);
+        release_milestone(&mut host, CLIENT, 0, 1).unwrap();
+        deliver(&mut host, 0);
+        assert_eq!(release_milestone(&mut host, OWNER, 0, 0), Err(ContractError::Unauthorized(OWNER)));
+        release_milestone(&mut host, CLIENT, 0, 0).unwrap();
+
+        assert_eq!(host.transfers, vec![(FREELANCER, 500), (FREELANCER, 300), (CLIENT, 200)]);
+        assert_eq!(storage::escrow_balance(&host, 0), 0);
+        assert_eq!(job_management::get_job(&host, 0).unwrap().2, JobStatus::Completed as u8);
+        assert_eq!(get_agreement(&host, 0).unwrap().4, AgreementStatus::Completed as u8);
+    }
+
+    #[test]
+    fn agreement_cannot_be_completed_directly() {
+        let mut host = with_two_milestones();
+        let result = update_agreement_status(&mut host, CLIENT, 0, AgreementStatus::Completed as u8);
+        assert_eq!(result, Err(ContractError::InvalidOperation));
+        assert!(host.transfers.is_empty());
+    }
+
+    #[test]
+    fn freelancer_cannot_release_own_payment() {
+        let mut host = with_two_milestones();
+        deliver(&mut host, 0);
+
+        assert_eq!(approve_milestone(&mut host, FREELANCER, 0, 1), Err(ContractError::InvalidTransition(0, 2)));
+        assert_eq!(release_milestone(&mut host, FREELANCER, 0, 0), Err(ContractError::Unauthorized(FREELANCER)));
+        assert!(host.transfers.is_empty());
+    }
+
+    #[test]
+    fn disputed_agreement_keeps_funds_escrowed() {
+        let mut host = with_two_milestones();
+        deliver(&mut host, 0);
+
+        update_agreement_status(&mut host, FREELANCER, 0, AgreementStatus::Disputed as u8).unwrap();
+        assert_eq!(release_milestone(&mut host, CLIENT, 0, 0), Err(ContractError::InvalidOperation));
+        assert_eq!(storage::escrow_balance(&host, 0), 1_000);
+        assert!(host.transfers.is_empty());
+    }
+
+    #[test]
+    fn arbitrator_splits_unpaid_amount() {
+        const ARBITRATOR: Address = [5u8; 20];
+        let mut host = with_two_milestones();
+        let config = Config { arbitrator: ARBITRATOR, ..admin::config(&host) };
+        admin::set_config(&mut host, OWNER, config).unwrap();
+        deliver(&mut host, 0);
+        release_milestone(&mut host, CLIENT, 0, 0).unwrap();
+        update_agreement_status(&mut host, CLIENT, 0, AgreementStatus::Disputed as u8).unwrap();
+
+        // 500 still at stake: the client gets 25% of it plus the 200 that was never bid
+        assert_eq!(resolve_dispute(&mut host, OWNER, 0, 2_500), Err(ContractError::Unauthorized(OWNER)));
+        resolve_dispute(&mut host, ARBITRATOR, 0, 2_500).unwrap();
+
+        assert_eq!(host.transfers, vec![(FREELANCER, 300), (FREELANCER, 375), (CLIENT, 325)]);
+        assert_eq!(storage::escrow_balance(&host, 0), 0);
+        assert_eq!(get_agreement(&host, 0).unwrap().4, AgreementStatus::Resolved as u8);
+        assert_eq!(job_management::get_job(&host, 0).unwrap().2, JobStatus::Completed as u8);
+        assert!(host.events.iter().any(|e| e.topics[0] == events::DISPUTE_RESOLVED));
+    }
+
+    #[test]
+    fn fee_is_fixed_at_agreement_and_taken_from_payouts() {
+        let mut host = with_accepted_proposal(1_000, 800);
+        let config = admin::config(&host);
+        admin::set_config(&mut host, OWNER, Config { fee_bps: 500, ..config }).unwrap();
+        create_agreement_from_proposal(&mut host, CLIENT, 0, &[(300, [0xaa; 32]), (500, [0xbb; 32])]).unwrap();
+        admin::set_config(&mut host, OWNER, Config { fee_bps: 1_000, ..config }).unwrap();
+        assert_eq!(get_payout_breakdown(&host, 0), Ok((800, 500, 40, 760, 0)));
+
+        deliver(&mut host, 0);
+        release_milestone(&mut host, CLIENT, 0, 0).unwrap();
+        assert_eq!(host.transfers, vec![(FREELANCER, 285)]);
+        assert_eq!(storage::treasury_balance(&host, &escrow::NATIVE_TOKEN), 15);
+        assert_eq!(get_payout_breakdown(&host, 0), Ok((800, 500, 40, 760, 300)));
+
+        // The freelancer's share of a dispute pays the same fee
+        update_agreement_status(&mut host, CLIENT, 0, AgreementStatus::Disputed as u8).unwrap();
+        resolve_dispute(&mut host, OWNER, 0, 6_000).unwrap();
+        assert_eq!(host.transfers, vec![(FREELANCER, 285), (FREELANCER, 190), (CLIENT, 500)]);
+        assert_eq!(storage::treasury_balance(&host, &escrow::NATIVE_TOKEN), 25);
+        assert_eq!(storage::escrow_balance(&host, 0), 0);
+        // The view still shows the full-schedule projection; the split is in the event
+        assert_eq!(get_payout_breakdown(&host, 0), Ok((800, 500, 40, 760, 300)));
+        let event = host.events.iter().rev().find(|e| e.topics[0] == events::DISPUTE_RESOLVED).unwrap();
+        assert_eq!(event.data[64..96], 200u128.to_word());
+    }
+
+    #[test]
+    fn owner_cannot_end_job_under_active_agreement() {
+        let mut host = with_two_milestones();
+        for status in [JobStatus::Cancelled, JobStatus::Completed] {
+            let result = job_management::update_job_status(&mut host, OWNER, 0, status as u8);
+            assert_eq!(result, Err(ContractError::Unauthorized(OWNER)));
         }
+
+        assert_eq!(job_management::get_job(&host, 0).unwrap().2, JobStatus::InProgress as u8);
+        assert_eq!(get_agreement(&host, 0).unwrap().4, AgreementStatus::Active as u8);
+        assert_eq!(storage::escrow_balance(&host, 0), 1_000);
+        assert!(host.transfers.is_empty());
+
+        // The milestones can still be paid from the untouched escrow
+        deliver(&mut host, 0);
+        release_milestone(&mut host, CLIENT, 0, 0).unwrap();
+        assert_eq!(host.transfers, vec![(FREELANCER, 300)]);
+    }
+
+    #[test]
+    fn full_refund_cancels_job() {
+        let mut host = with_two_milestones();
+        update_agreement_status(&mut host, FREELANCER, 0, AgreementStatus::Disputed as u8).unwrap();
+        resolve_dispute(&mut host, OWNER, 0, 10_000).unwrap();
+
+        assert_eq!(host.transfers, vec![(CLIENT, 1_000)]);
+        assert_eq!(job_management::get_job(&host, 0).unwrap().2, JobStatus::Cancelled as u8);
+    }
+
+    #[test]
+    fn refund_after_paid_milestones_completes_job() {
+        let mut host = with_two_milestones();
+        deliver(&mut host, 0);
+        release_milestone(&mut host, CLIENT, 0, 0).unwrap();
+        update_agreement_status(&mut host, CLIENT, 0, AgreementStatus::Disputed as u8).unwrap();
+        resolve_dispute(&mut host, OWNER, 0, 10_000).unwrap();
+
+        assert_eq!(host.transfers, vec![(FREELANCER, 300), (CLIENT, 700)]);
+        assert_eq!(job_management::get_job(&host, 0).unwrap().2, JobStatus::Completed as u8);
+    }
+
+    #[test]
+    fn only_disputed_agreements_can_be_resolved() {
+        let mut host = with_two_milestones();
+        assert_eq!(resolve_dispute(&mut host, OWNER, 0, 5_000), Err(ContractError::InvalidTransition(0, 3)));
+
+        update_agreement_status(&mut host, CLIENT, 0, AgreementStatus::Disputed as u8).unwrap();
+        assert_eq!(resolve_dispute(&mut host, OWNER, 0, 10_001), Err(ContractError::InvalidInput));
+        resolve_dispute(&mut host, OWNER, 0, 5_000).unwrap();
+        assert_eq!(resolve_dispute(&mut host, OWNER, 0, 5_000), Err(ContractError::InvalidTransition(3, 3)));
+        assert_eq!(
+            update_agreement_status(&mut host, CLIENT, 0, AgreementStatus::Disputed as u8),
+            Err(ContractError::InvalidTransition(3, 2))
+        );
+    }
+
+
+    #[test]
+    fn list_agreements_for_either_party() {
+        let mut host = with_two_milestones();
+        job_management::create_job(&mut host, STRANGER, &job_params(100), 100).unwrap();
+        proposal_management::submit_proposal(&mut host, 1, FREELANCER, 100, 0, [0u8; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, STRANGER, 1, ProposalStatus::Accepted as u8).unwrap();
+        create_agreement_from_proposal(&mut host, STRANGER, 1, &[]).unwrap();
+        let mut page = [0; MAX_PAGE_SIZE];
+
+        assert_eq!(list_agreements_for_user(&host, &FREELANCER, 0, 10, &mut page), (2, 2));
+        assert_eq!(page[..2], [0, 1]);
+        assert_eq!(list_agreements_for_user(&host, &CLIENT, 0, 10, &mut page), (1, 1));
+        assert_eq!(page[0], 0);
+        assert_eq!(list_agreements_for_user(&host, &STRANGER, 1, 10, &mut page), (1, 0));
+        assert_eq!(list_agreements_for_user(&host, &OWNER, 0, 10, &mut page), (0, 0));
     }
 }
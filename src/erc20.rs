@@ -0,0 +1,94 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/erc20.rs
+
+// Calls into ERC-20 token contracts.
+// Both calls go out without value and without allowing re-entry (see Host::call), and only
+// count as successful if the token returns `true`. Tokens that return nothing are not supported.
+
+use crate::abi::{Word, WORD};
+use crate::keccak::selector;
+use crate::types::{Address, Balance, ContractError};
+use crate::host::Host;
+
+const TRANSFER: u32 = selector("transfer(address,uint256)");
+const TRANSFER_FROM: u32 = selector("transferFrom(address,address,uint256)");
+
+// Largest number of arguments we pass to a token.
+const MAX_CALL_ARGS: usize = 3;
+
+/// Sends `amount` of `token` from this contract to `to`.
+pub fn transfer(host: &mut impl Host, token: &Address, to: &Address, amount: Balance) -> Result<(), ContractError> {
+    if call(host, token, TRANSFER, &[to.to_word(), amount.to_word()]) {
+        return Ok(());
+    }
+    Err(ContractError::TransferFailed(*to, amount))
+}
+
+/// Pulls `amount` of `token` from `from` into this contract. `from` must have approved it first.
+pub fn transfer_from(host: &mut impl Host, token: &Address, from: &Address, amount: Balance) -> Result<(), ContractError> {
+    let this = host.address();
+    if call(host, token, TRANSFER_FROM, &[from.to_word(), this.to_word(), amount.to_word()]) {
+        return Ok(());
+    }
+    Err(ContractError::TransferFailed(this, amount))
+}
+
+/// Calls `token` and returns whether it succeeded and returned `true`.
+fn call(host: &mut impl Host, token: &Address, function: u32, args: &[[u8; WORD]]) -> bool {
+    let mut input = [0u8; 4 + MAX_CALL_ARGS * WORD];
+    input[..4].copy_from_slice(&function.to_be_bytes());
+    for (i, arg) in args.iter().enumerate() {
+        input[4 + i * WORD..4 + (i + 1) * WORD].copy_from_slice(arg);
+    }
+    let mut output = [0u8; WORD];
+    match host.call(token, &[0u8; 32], &input[..4 + args.len() * WORD], &mut output) {
+        Some(WORD) => bool::from_word(&output) == Some(true),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockHost, MockToken};
+
+    const TOKEN: Address = [0x70; 20];
+    const CLIENT: Address = [1u8; 20];
+    const FREELANCER: Address = [2u8; 20];
+
+    fn with_token(client_balance: Balance, allowance: Balance) -> MockHost {
+        let mut host = MockHost::new();
+        let mut token = MockToken::default();
+        token.balances.insert(CLIENT, client_balance);
+        token.allowances.insert((CLIENT, host.address), allowance);
+        host.tokens.insert(TOKEN, token);
+        host
+    }
+
+    #[test]
+    fn transfer_from_needs_allowance_and_balance() {
+        let mut host = with_token(1_000, 600);
+        let this = host.address;
+        assert_eq!(transfer_from(&mut host, &TOKEN, &CLIENT, 700), Err(ContractError::TransferFailed(this, 700)));
+
+        transfer_from(&mut host, &TOKEN, &CLIENT, 600).unwrap();
+        assert_eq!(host.token_balance(&TOKEN, &CLIENT), 400);
+        assert_eq!(host.token_balance(&TOKEN, &this), 600);
+        assert_eq!(transfer_from(&mut host, &TOKEN, &CLIENT, 1), Err(ContractError::TransferFailed(this, 1)));
+    }
+
+    #[test]
+    fn transfer_checks_return_value() {
+        let mut host = with_token(1_000, 1_000);
+        transfer_from(&mut host, &TOKEN, &CLIENT, 500).unwrap();
+        transfer(&mut host, &TOKEN, &FREELANCER, 200).unwrap();
+        assert_eq!(host.token_balance(&TOKEN, &FREELANCER), 200);
+        assert_eq!(transfer(&mut host, &TOKEN, &FREELANCER, 301), Err(ContractError::TransferFailed(FREELANCER, 301)));
+
+        // A token that returns false has not moved anything as far as we are concerned
+        host.tokens.get_mut(&TOKEN).unwrap().returns_false = true;
+        assert_eq!(transfer(&mut host, &TOKEN, &FREELANCER, 100), Err(ContractError::TransferFailed(FREELANCER, 100)));
+
+        // Nor does calling an address that is not a token
+        assert_eq!(transfer(&mut host, &[0x71; 20], &FREELANCER, 100), Err(ContractError::TransferFailed(FREELANCER, 100)));
+    }
+}
@@ -0,0 +1,108 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/errors.rs
+
+// Revert data for ContractError.
+// Each variant maps to a Solidity custom error: a 4-byte selector followed by the
+// ABI-encoded arguments, so `cast` and `try/catch` in Solidity can decode it.
+// The declarations live in IWeb3LancerPolkadot (call_from_sol.sol).
+
+use crate::abi::{Word, WORD};
+use crate::keccak::selector;
+use crate::types::ContractError;
+
+const INVALID_OPERATION: u32 = selector("InvalidOperation()");
+const NOT_FOUND: u32 = selector("NotFound(uint32)");
+const ALREADY_EXISTS: u32 = selector("AlreadyExists()");
+const STORAGE_FULL: u32 = selector("StorageFull()");
+const INVALID_INPUT: u32 = selector("InvalidInput()");
+const UNAUTHORIZED: u32 = selector("Unauthorized(address)");
+const TRANSFER_FAILED: u32 = selector("TransferFailed(address,uint128)");
+const INVALID_TRANSITION: u32 = selector("InvalidTransition(uint8,uint8)");
+const INSUFFICIENT_ESCROW: u32 = selector("InsufficientEscrow(uint128,uint128)");
+const UNKNOWN_SELECTOR: u32 = selector("UnknownSelector(bytes4)");
+const DEADLINE_PASSED: u32 = selector("DeadlinePassed(uint64)");
+const ENFORCED_PAUSE: u32 = selector("EnforcedPause()");
+
+// Largest number of arguments any error carries.
+const MAX_ERROR_ARGS: usize = 2;
+
+/// Longest revert payload: selector + arguments.
+pub const MAX_REVERT_LEN: usize = 4 + MAX_ERROR_ARGS * WORD;
+
+impl ContractError {
+    /// Writes the selector and arguments of the matching Solidity error into `output`.
+    /// Returns the number of bytes written.
+    pub fn encode(&self, output: &mut [u8; MAX_REVERT_LEN]) -> usize {
+        match *self {
+            ContractError::InvalidOperation => write(output, INVALID_OPERATION, &[]),
+            ContractError::NotFound(id) => write(output, NOT_FOUND, &[id.to_word()]),
+            ContractError::AlreadyExists => write(output, ALREADY_EXISTS, &[]),
+            ContractError::StorageFull => write(output, STORAGE_FULL, &[]),
+            ContractError::InvalidInput => write(output, INVALID_INPUT, &[]),
+            ContractError::Unauthorized(caller) => write(output, UNAUTHORIZED, &[caller.to_word()]),
+            ContractError::TransferFailed(to, amount) => {
+                write(output, TRANSFER_FAILED, &[to.to_word(), amount.to_word()])
+            }
+            ContractError::InvalidTransition(from, to) => {
+                write(output, INVALID_TRANSITION, &[from.to_word(), to.to_word()])
+            }
+            ContractError::InsufficientEscrow(available, required) => {
+                write(output, INSUFFICIENT_ESCROW, &[available.to_word(), required.to_word()])
+            }
+            ContractError::UnknownSelector(unknown) => {
+                write(output, UNKNOWN_SELECTOR, &[unknown.to_be_bytes().to_word()])
+            }
+            ContractError::DeadlinePassed(deadline) => write(output, DEADLINE_PASSED, &[deadline.to_word()]),
+            ContractError::EnforcedPause => write(output, ENFORCED_PAUSE, &[]),
+        }
+    }
+}
+
+fn write(output: &mut [u8; MAX_REVERT_LEN], error_selector: u32, args: &[[u8; WORD]]) -> usize {
+    output[..4].copy_from_slice(&error_selector.to_be_bytes());
+    for (i, word) in args.iter().enumerate() {
+        output[4 + i * WORD..4 + (i + 1) * WORD].copy_from_slice(word);
+    }
+    4 + args.len() * WORD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::Decoder;
+
+    fn encoded(error: ContractError) -> ([u8; MAX_REVERT_LEN], usize) {
+        let mut output = [0u8; MAX_REVERT_LEN];
+        let len = error.encode(&mut output);
+        (output, len)
+    }
+
+    #[test]
+    fn errors_without_arguments_are_just_the_selector() {
+        let (output, len) = encoded(ContractError::InvalidOperation);
+        assert_eq!(len, 4);
+        assert_eq!(output[..4], selector("InvalidOperation()").to_be_bytes());
+    }
+
+    #[test]
+    fn arguments_round_trip() {
+        let (output, len) = encoded(ContractError::TransferFailed([0xab; 20], 1_234));
+        assert_eq!(len, MAX_REVERT_LEN);
+        assert_eq!(output[..4], selector("TransferFailed(address,uint128)").to_be_bytes());
+        let mut decoder = Decoder::new(&output[4..len]);
+        assert_eq!(decoder.read::<[u8; 20]>(), Ok([0xab; 20]));
+        assert_eq!(decoder.read::<u128>(), Ok(1_234));
+
+        let (output, len) = encoded(ContractError::InvalidTransition(2, 3));
+        let mut decoder = Decoder::new(&output[4..len]);
+        assert_eq!(decoder.read::<u8>(), Ok(2));
+        assert_eq!(decoder.read::<u8>(), Ok(3));
+    }
+
+    #[test]
+    fn unknown_selector_is_a_left_aligned_bytes4() {
+        let (output, len) = encoded(ContractError::UnknownSelector(0xdeadbeef));
+        assert_eq!(len, 4 + WORD);
+        assert_eq!(output[..4], selector("UnknownSelector(bytes4)").to_be_bytes());
+        assert_eq!(Decoder::new(&output[4..len]).read::<[u8; 4]>(), Ok([0xde, 0xad, 0xbe, 0xef]));
+    }
+}
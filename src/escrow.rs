@@ -0,0 +1,196 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/escrow.rs
+
+// Escrow, tracked per job, in the job's payment token.
+// Native funds arrive with payable calls (createJob / fundJob); ERC-20 funds are pulled with
+// transferFrom (fundJobWithToken). Either way they sit in the contract's own balance and the
+// per-job amount is kept in storage so one job can never spend another's funds.
+// Storage is always updated before any transfer goes out.
+// Platform fees move from a job's escrow into the treasury balance of its token, which only
+// the owner can withdraw.
+
+use crate::types::{Address, Balance, ContractError, JobId};
+use crate::host::Host;
+use crate::storage;
+use crate::events;
+use crate::erc20;
+
+/// payment_token of jobs paid in the chain's native token.
+pub const NATIVE_TOKEN: Address = [0u8; 20];
+
+/// Value sent along with the current call.
+/// pallet_revive hands us a little-endian U256; anything that doesn't fit a Balance is rejected.
+pub fn value_transferred(host: &impl Host) -> Result<Balance, ContractError> {
+    let value = host.value_transferred();
+    if value[16..].iter().any(|b| *b != 0) {
+        return Err(ContractError::InvalidInput);
+    }
+    Ok(u128::from_le_bytes(value[..16].try_into().unwrap()))
+}
+
+/// Adds `amount` to the escrow of `job_id`.
+pub fn lock(host: &mut impl Host, job_id: JobId, amount: Balance) -> Result<(), ContractError> {
+    let balance = storage::escrow_balance(host, job_id)
+        .checked_add(amount)
+        .ok_or(ContractError::InvalidInput)?;
+    storage::set_escrow_balance(host, job_id, balance);
+    Ok(())
+}
+
+/// Pulls `amount` of `token` from `from` and adds it to the escrow of `job_id`.
+/// `from` must have approved the contract for at least `amount`.
+pub fn lock_tokens(host: &mut impl Host, job_id: JobId, token: &Address, from: &Address, amount: Balance) -> Result<(), ContractError> {
+    // Tokens arrive before they are credited, the same order as native value
+    erc20::transfer_from(host, token, from, amount)?;
+    lock(host, job_id, amount)
+}
+
+/// Pays `amount` out of the escrow of `job_id` to `to`.
+pub fn release(host: &mut impl Host, job_id: JobId, to: &Address, amount: Balance) -> Result<(), ContractError> {
+    let balance = storage::escrow_balance(host, job_id);
+    if amount > balance {
+        return Err(ContractError::InsufficientEscrow(balance, amount));
+    }
+    storage::set_escrow_balance(host, job_id, balance - amount);
+    transfer(host, &payment_token(host, job_id), to, amount)?;
+    if amount > 0 {
+        events::escrow_released(host, job_id, to, amount);
+    }
+    Ok(())
+}
+
+/// Pays `amount` out of the escrow of `job_id` to `to`, keeping a `fee_bps` platform fee
+/// in the treasury. Returns the fee.
+pub fn release_with_fee(host: &mut impl Host, job_id: JobId, to: &Address, amount: Balance, fee_bps: u16) -> Result<Balance, ContractError> {
+    let balance = storage::escrow_balance(host, job_id);
+    if amount > balance {
+        return Err(ContractError::InsufficientEscrow(balance, amount));
+    }
+    let fee = share_of(amount, fee_bps);
+    if fee > 0 {
+        storage::set_escrow_balance(host, job_id, balance - fee);
+        let token = payment_token(host, job_id);
+        let treasury = storage::treasury_balance(host, &token).checked_add(fee).ok_or(ContractError::InvalidOperation)?;
+        storage::set_treasury_balance(host, &token, treasury);
+        events::fee_collected(host, job_id, fee);
+    }
+    release(host, job_id, to, amount - fee)?;
+    Ok(fee)
+}
+
+/// Sends `amount` of the fees collected in `token` to `to`.
+pub fn withdraw_fees(host: &mut impl Host, token: &Address, to: &Address, amount: Balance) -> Result<(), ContractError> {
+    let treasury = storage::treasury_balance(host, token);
+    if amount > treasury {
+        return Err(ContractError::InsufficientEscrow(treasury, amount));
+    }
+    storage::set_treasury_balance(host, token, treasury - amount);
+    transfer(host, token, to, amount)?;
+    events::fees_withdrawn(host, token, to, amount);
+    Ok(())
+}
+
+/// Returns whatever is left in the escrow of `job_id` to `to`.
+pub fn refund(host: &mut impl Host, job_id: JobId, to: &Address) -> Result<(), ContractError> {
+    let balance = storage::escrow_balance(host, job_id);
+    release(host, job_id, to, balance)
+}
+
+/// Basis points in 100%.
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+/// `bps` basis points of `amount`, rounded down. Cannot overflow for any amount.
+pub fn share_of(amount: Balance, bps: u16) -> Balance {
+    let bps = bps.min(BPS_DENOMINATOR) as Balance;
+    let denominator = BPS_DENOMINATOR as Balance;
+    amount / denominator * bps + amount % denominator * bps / denominator
+}
+
+/// The token the escrow of `job_id` is held in.
+fn payment_token(host: &impl Host, job_id: JobId) -> Address {
+    storage::load_job(host, job_id).map_or(NATIVE_TOKEN, |job| job.payment_token)
+}
+
+/// Sends `amount` of `token` to `to`: an ERC-20 transfer, or a plain value transfer
+/// (a call with no input data) for the native token.
+fn transfer(host: &mut impl Host, token: &Address, to: &Address, amount: Balance) -> Result<(), ContractError> {
+    if amount == 0 {
+        return Ok(());
+    }
+    if *token != NATIVE_TOKEN {
+        return erc20::transfer(host, token, to, amount);
+    }
+    let mut value = [0u8; 32];
+    value[..16].copy_from_slice(&amount.to_le_bytes());
+    host.call(to, &value, &[], &mut [])
+        .map(|_| ())
+        .ok_or(ContractError::TransferFailed(*to, amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job_management;
+    use crate::mock::{job_params, MockHost, MockToken};
+    use crate::types::JobParams;
+
+    #[test]
+    fn share_of_rounds_down_without_overflow() {
+        assert_eq!(share_of(1_000, 2_500), 250);
+        assert_eq!(share_of(999, 5_000), 499);
+        assert_eq!(share_of(1_000, BPS_DENOMINATOR), 1_000);
+        assert_eq!(share_of(u128::MAX, BPS_DENOMINATOR), u128::MAX);
+        assert_eq!(share_of(u128::MAX, 5_000), u128::MAX / 2);
+    }
+
+    #[test]
+    fn release_with_fee_moves_fee_to_treasury() {
+        const FREELANCER: Address = [2u8; 20];
+        let mut host = MockHost::new();
+        lock(&mut host, 0, 1_000).unwrap();
+
+        assert_eq!(release_with_fee(&mut host, 0, &FREELANCER, 600, 250), Ok(15));
+        assert_eq!(host.transferred_to(&FREELANCER), 585);
+        assert_eq!(storage::escrow_balance(&host, 0), 400);
+        assert_eq!(storage::treasury_balance(&host, &NATIVE_TOKEN), 15);
+        assert_eq!(
+            release_with_fee(&mut host, 0, &FREELANCER, 401, 250),
+            Err(ContractError::InsufficientEscrow(400, 401))
+        );
+
+        assert_eq!(
+            withdraw_fees(&mut host, &NATIVE_TOKEN, &FREELANCER, 16),
+            Err(ContractError::InsufficientEscrow(15, 16))
+        );
+        withdraw_fees(&mut host, &NATIVE_TOKEN, &FREELANCER, 15).unwrap();
+        assert_eq!(storage::treasury_balance(&host, &NATIVE_TOKEN), 0);
+        assert_eq!(host.transferred_to(&FREELANCER), 600);
+    }
+
+    #[test]
+    fn token_escrow_pays_out_in_the_job_token() {
+        const CLIENT: Address = [1u8; 20];
+        const FREELANCER: Address = [2u8; 20];
+        const TOKEN: Address = [0x70; 20];
+        let mut host = MockHost::new();
+        let mut token = MockToken::default();
+        token.balances.insert(CLIENT, 1_000);
+        token.allowances.insert((CLIENT, host.address), 1_000);
+        host.tokens.insert(TOKEN, token);
+        job_management::create_job(&mut host, CLIENT, &JobParams { payment_token: TOKEN, ..job_params(1_000) }, 0).unwrap();
+
+        lock_tokens(&mut host, 0, &TOKEN, &CLIENT, 1_000).unwrap();
+        assert_eq!(storage::escrow_balance(&host, 0), 1_000);
+        assert_eq!(release_with_fee(&mut host, 0, &FREELANCER, 600, 250), Ok(15));
+        refund(&mut host, 0, &CLIENT).unwrap();
+
+        assert_eq!(host.token_balance(&TOKEN, &FREELANCER), 585);
+        assert_eq!(host.token_balance(&TOKEN, &CLIENT), 400);
+        assert_eq!(host.token_balance(&TOKEN, &host.address), 15);
+        assert_eq!(storage::treasury_balance(&host, &TOKEN), 15);
+        assert_eq!(storage::treasury_balance(&host, &NATIVE_TOKEN), 0);
+        assert!(host.transfers.is_empty()); // No native value moved
+
+        withdraw_fees(&mut host, &TOKEN, &CLIENT, 15).unwrap();
+        assert_eq!(host.token_balance(&TOKEN, &CLIENT), 415);
+    }
+}
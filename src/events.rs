@@ -0,0 +1,207 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/events.rs
+
+// Solidity-compatible events, declared in IWeb3LancerPolkadot (call_from_sol.sol).
+// topics[0] is keccak256 of the event signature; indexed parameters follow as one
+// 32-byte word each, and the remaining parameters are ABI-encoded into the data.
+
+use crate::abi::{Word, WORD};
+use crate::keccak::keccak256;
+use crate::types::{Address, AgreementId, Balance, Config, CounterOffer, Job, JobId, MilestoneIndex, Proposal, ProposalId};
+use crate::host::Host;
+
+pub const JOB_CREATED: [u8; 32] = keccak256(b"JobCreated(uint32,address,address,uint128,bytes32,uint32,uint64)");
+pub const JOB_FUNDED: [u8; 32] = keccak256(b"JobFunded(uint32,uint128,uint128)");
+pub const JOB_STATUS_CHANGED: [u8; 32] = keccak256(b"JobStatusChanged(uint32,uint8,uint8)");
+pub const ESCROW_RELEASED: [u8; 32] = keccak256(b"EscrowReleased(uint32,address,uint128)");
+pub const FEE_COLLECTED: [u8; 32] = keccak256(b"FeeCollected(uint32,uint128)");
+pub const FEES_WITHDRAWN: [u8; 32] = keccak256(b"FeesWithdrawn(address,address,uint128)");
+pub const PROPOSAL_SUBMITTED: [u8; 32] = keccak256(b"ProposalSubmitted(uint32,uint32,address,uint128,bytes32)");
+pub const PROPOSAL_UPDATED: [u8; 32] = keccak256(b"ProposalUpdated(uint32,uint128,bytes32)");
+pub const COUNTER_OFFERED: [u8; 32] = keccak256(b"CounterOffered(uint32,address,uint32,uint128,bytes32)");
+pub const PROPOSAL_STATUS_CHANGED: [u8; 32] = keccak256(b"ProposalStatusChanged(uint32,uint8,uint8)");
+pub const AGREEMENT_CREATED: [u8; 32] = keccak256(b"AgreementCreated(uint32,uint32,address,address,uint128)");
+pub const AGREEMENT_STATUS_CHANGED: [u8; 32] = keccak256(b"AgreementStatusChanged(uint32,uint8,uint8)");
+pub const DISPUTE_RESOLVED: [u8; 32] = keccak256(b"DisputeResolved(uint32,uint16,uint128,uint128)");
+pub const OWNERSHIP_TRANSFER_STARTED: [u8; 32] = keccak256(b"OwnershipTransferStarted(address,address)");
+pub const OWNERSHIP_TRANSFERRED: [u8; 32] = keccak256(b"OwnershipTransferred(address,address)");
+pub const CONFIG_UPDATED: [u8; 32] = keccak256(b"ConfigUpdated(address,uint16,uint128,uint32)");
+pub const PAUSED: [u8; 32] = keccak256(b"Paused(address)");
+pub const UNPAUSED: [u8; 32] = keccak256(b"Unpaused(address)");
+pub const UPGRADED: [u8; 32] = keccak256(b"Upgraded(bytes32)");
+pub const STORAGE_MIGRATED: [u8; 32] = keccak256(b"StorageMigrated(uint8)");
+pub const REVIEW_SUBMITTED: [u8; 32] = keccak256(b"ReviewSubmitted(uint32,address,address,uint8,bytes32)");
+pub const MILESTONE_STATUS_CHANGED: [u8; 32] = keccak256(b"MilestoneStatusChanged(uint32,uint32,uint8,uint8)");
+
+// Largest number of non-indexed words any event carries.
+const MAX_DATA_WORDS: usize = 4;
+
+/// Deposits an event whose non-indexed data is a tuple of static words.
+fn emit(host: &mut impl Host, topics: &[[u8; 32]], data: &[[u8; WORD]]) {
+    let mut buf = [0u8; MAX_DATA_WORDS * WORD];
+    for (i, word) in data.iter().enumerate() {
+        buf[i * WORD..(i + 1) * WORD].copy_from_slice(word);
+    }
+    host.deposit_event(topics, &buf[..data.len() * WORD]);
+}
+
+// --- Jobs ---
+
+/// JobCreated(uint32 indexed jobId, address indexed client, address indexed paymentToken, uint128 budget,
+///            bytes32 contentCid, uint32 category, uint64 deadline)
+pub fn job_created(host: &mut impl Host, job: &Job) {
+    emit(
+        host,
+        &[JOB_CREATED, job.id.to_word(), job.client_id.to_word(), job.payment_token.to_word()],
+        &[job.budget.to_word(), job.content_cid.to_word(), job.category.to_word(), job.deadline.to_word()],
+    );
+}
+
+/// JobFunded(uint32 indexed jobId, uint128 amount, uint128 escrowBalance)
+pub fn job_funded(host: &mut impl Host, job_id: JobId, amount: Balance, escrow_balance: Balance) {
+    emit(host, &[JOB_FUNDED, job_id.to_word()], &[amount.to_word(), escrow_balance.to_word()]);
+}
+
+/// JobStatusChanged(uint32 indexed jobId, uint8 oldStatus, uint8 newStatus)
+pub fn job_status_changed(host: &mut impl Host, job_id: JobId, old_status: u8, new_status: u8) {
+    emit(host, &[JOB_STATUS_CHANGED, job_id.to_word()], &[old_status.to_word(), new_status.to_word()]);
+}
+
+/// EscrowReleased(uint32 indexed jobId, address indexed to, uint128 amount)
+pub fn escrow_released(host: &mut impl Host, job_id: JobId, to: &Address, amount: Balance) {
+    emit(host, &[ESCROW_RELEASED, job_id.to_word(), to.to_word()], &[amount.to_word()]);
+}
+
+/// FeeCollected(uint32 indexed jobId, uint128 amount)
+pub fn fee_collected(host: &mut impl Host, job_id: JobId, amount: Balance) {
+    emit(host, &[FEE_COLLECTED, job_id.to_word()], &[amount.to_word()]);
+}
+
+/// FeesWithdrawn(address indexed token, address indexed to, uint128 amount)
+pub fn fees_withdrawn(host: &mut impl Host, token: &Address, to: &Address, amount: Balance) {
+    emit(host, &[FEES_WITHDRAWN, token.to_word(), to.to_word()], &[amount.to_word()]);
+}
+
+// --- Proposals ---
+
+/// ProposalSubmitted(uint32 indexed proposalId, uint32 indexed jobId, address indexed freelancer, uint128 bidAmount, bytes32 coverHash)
+pub fn proposal_submitted(host: &mut impl Host, proposal: &Proposal) {
+    emit(
+        host,
+        &[PROPOSAL_SUBMITTED, proposal.id.to_word(), proposal.job_id.to_word(), proposal.freelancer_id.to_word()],
+        &[proposal.bid_amount.to_word(), proposal.cover_hash],
+    );
+}
+
+/// ProposalUpdated(uint32 indexed proposalId, uint128 bidAmount, bytes32 coverHash)
+pub fn proposal_updated(host: &mut impl Host, proposal: &Proposal) {
+    emit(host, &[PROPOSAL_UPDATED, proposal.id.to_word()], &[proposal.bid_amount.to_word(), proposal.cover_hash]);
+}
+
+/// CounterOffered(uint32 indexed proposalId, address indexed from, uint32 round, uint128 amount, bytes32 termsHash)
+pub fn counter_offered(host: &mut impl Host, offer: &CounterOffer) {
+    emit(
+        host,
+        &[COUNTER_OFFERED, offer.proposal_id.to_word(), offer.from.to_word()],
+        &[offer.round.to_word(), offer.amount.to_word(), offer.terms_hash],
+    );
+}
+
+/// ProposalStatusChanged(uint32 indexed proposalId, uint8 oldStatus, uint8 newStatus)
+pub fn proposal_status_changed(host: &mut impl Host, proposal_id: ProposalId, old_status: u8, new_status: u8) {
+    emit(
+        host,
+        &[PROPOSAL_STATUS_CHANGED, proposal_id.to_word()],
+        &[old_status.to_word(), new_status.to_word()],
+    );
+}
+
+// --- Agreements ---
+
+/// AgreementCreated(uint32 indexed agreementId, uint32 jobId, address indexed client, address indexed freelancer, uint128 totalAmount)
+pub fn agreement_created(host: &mut impl Host, agreement_id: AgreementId, job_id: JobId, client: &Address, freelancer: &Address, total_amount: Balance) {
+    emit(
+        host,
+        &[AGREEMENT_CREATED, agreement_id.to_word(), client.to_word(), freelancer.to_word()],
+        &[job_id.to_word(), total_amount.to_word()],
+    );
+}
+
+/// AgreementStatusChanged(uint32 indexed agreementId, uint8 oldStatus, uint8 newStatus)
+pub fn agreement_status_changed(host: &mut impl Host, agreement_id: AgreementId, old_status: u8, new_status: u8) {
+    emit(
+        host,
+        &[AGREEMENT_STATUS_CHANGED, agreement_id.to_word()],
+        &[old_status.to_word(), new_status.to_word()],
+    );
+}
+
+/// MilestoneStatusChanged(uint32 indexed agreementId, uint32 indexed index, uint8 oldStatus, uint8 newStatus)
+pub fn milestone_status_changed(host: &mut impl Host, agreement_id: AgreementId, index: MilestoneIndex, old_status: u8, new_status: u8) {
+    emit(
+        host,
+        &[MILESTONE_STATUS_CHANGED, agreement_id.to_word(), index.to_word()],
+        &[old_status.to_word(), new_status.to_word()],
+    );
+}
+
+/// DisputeResolved(uint32 indexed agreementId, uint16 clientShareBps, uint128 clientAmount, uint128 freelancerAmount)
+pub fn dispute_resolved(host: &mut impl Host, agreement_id: AgreementId, client_share_bps: u16, client_amount: Balance, freelancer_amount: Balance) {
+    emit(
+        host,
+        &[DISPUTE_RESOLVED, agreement_id.to_word()],
+        &[client_share_bps.to_word(), client_amount.to_word(), freelancer_amount.to_word()],
+    );
+}
+
+// --- Reputation ---
+
+/// ReviewSubmitted(uint32 indexed agreementId, address indexed reviewer, address indexed reviewee, uint8 score, bytes32 reviewHash)
+pub fn review_submitted(host: &mut impl Host, agreement_id: AgreementId, reviewer: &Address, reviewee: &Address, score: u8, review_hash: &[u8; 32]) {
+    emit(
+        host,
+        &[REVIEW_SUBMITTED, agreement_id.to_word(), reviewer.to_word(), reviewee.to_word()],
+        &[score.to_word(), review_hash.to_word()],
+    );
+}
+
+// --- Admin ---
+
+/// OwnershipTransferStarted(address indexed previousOwner, address indexed newOwner)
+pub fn ownership_transfer_started(host: &mut impl Host, previous_owner: &Address, new_owner: &Address) {
+    emit(host, &[OWNERSHIP_TRANSFER_STARTED, previous_owner.to_word(), new_owner.to_word()], &[]);
+}
+
+/// OwnershipTransferred(address indexed previousOwner, address indexed newOwner)
+pub fn ownership_transferred(host: &mut impl Host, previous_owner: &Address, new_owner: &Address) {
+    emit(host, &[OWNERSHIP_TRANSFERRED, previous_owner.to_word(), new_owner.to_word()], &[]);
+}
+
+/// Paused(address account) or Unpaused(address account), depending on `paused`.
+pub fn pause_changed(host: &mut impl Host, account: &Address, paused: bool) {
+    let topic = if paused { PAUSED } else { UNPAUSED };
+    emit(host, &[topic], &[account.to_word()]);
+}
+
+/// Upgraded(bytes32 indexed codeHash)
+pub fn upgraded(host: &mut impl Host, code_hash: &[u8; 32]) {
+    emit(host, &[UPGRADED, *code_hash], &[]);
+}
+
+/// StorageMigrated(uint8 version)
+pub fn storage_migrated(host: &mut impl Host, version: u8) {
+    emit(host, &[STORAGE_MIGRATED], &[version.to_word()]);
+}
+
+/// ConfigUpdated(address arbitrator, uint16 feeBps, uint128 minJobBudget, uint32 maxMilestones)
+pub fn config_updated(host: &mut impl Host, config: &Config) {
+    emit(
+        host,
+        &[CONFIG_UPDATED],
+        &[
+            config.arbitrator.to_word(),
+            config.fee_bps.to_word(),
+            config.min_job_budget.to_word(),
+            config.max_milestones.to_word(),
+        ],
+    );
+}
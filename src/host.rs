@@ -0,0 +1,135 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/host.rs
+
+// Host abstraction.
+// All contract logic talks to the chain through `Host` instead of calling `HostFnImpl`
+// directly, so the same code runs on PolkaVM (ApiHost) and under `cargo test` on the
+// build machine (mock::MockHost, behind the `std` feature).
+
+use crate::types::Address;
+
+/// The subset of pallet_revive host functions the contract uses.
+pub trait Host {
+    /// Address of this contract.
+    fn address(&self) -> Address;
+
+    /// Address of the account (or contract) that called us.
+    fn caller(&self) -> Address;
+
+    /// Value sent with the current call, as a little-endian U256.
+    fn value_transferred(&self) -> [u8; 32];
+
+    /// Timestamp of the current block, in unix seconds.
+    fn now(&self) -> u64;
+
+    fn call_data_size(&self) -> usize;
+
+    /// Copies the call data into `output` (which must be `call_data_size()` bytes long).
+    fn call_data_copy(&self, output: &mut [u8]);
+
+    /// Reads the value under `key` into `output`.
+    /// Returns its length, or None if the key is unset. On pallet_revive a value longer than
+    /// `output` traps instead, so callers size the buffer for the largest value they store
+    /// (storage.rs reads everything into a MAX_RECORD_LEN buffer).
+    fn get_storage(&self, key: &[u8; 32], output: &mut [u8]) -> Option<usize>;
+
+    fn set_storage(&mut self, key: &[u8; 32], value: &[u8]);
+
+    /// Calls `callee` with `value` (little-endian U256) and `input`, without allowing re-entry.
+    /// Returns the length of the return data copied into `output`, or None if the call failed.
+    fn call(&mut self, callee: &Address, value: &[u8; 32], input: &[u8], output: &mut [u8]) -> Option<usize>;
+
+    fn deposit_event(&mut self, topics: &[[u8; 32]], data: &[u8]);
+
+    /// Replaces the contract's code with the already uploaded code `code_hash`.
+    /// Storage is kept; the new code runs from the next call on.
+    fn set_code_hash(&mut self, code_hash: &[u8; 32]);
+
+    /// Ends the call with `data`. On PolkaVM this never returns.
+    fn return_value(&mut self, revert: bool, data: &[u8]);
+}
+
+/// The real host: pallet_revive's API on PolkaVM.
+#[cfg(not(feature = "std"))]
+pub struct ApiHost;
+
+#[cfg(not(feature = "std"))]
+mod api_host {
+    use super::{ApiHost, Host};
+    use crate::types::Address;
+    use uapi::{CallFlags, HostFn, HostFnImpl as api, ReturnFlags, StorageFlags};
+
+    impl Host for ApiHost {
+        fn address(&self) -> Address {
+            let mut address = [0u8; 20];
+            api::address(&mut address);
+            address
+        }
+
+        fn caller(&self) -> Address {
+            let mut caller = [0u8; 20];
+            api::caller(&mut caller);
+            caller
+        }
+
+        fn value_transferred(&self) -> [u8; 32] {
+            let mut value = [0u8; 32];
+            api::value_transferred(&mut value);
+            value
+        }
+
+        fn now(&self) -> u64 {
+            // Little-endian U256; seconds fit comfortably in the low 8 bytes
+            let mut now = [0u8; 32];
+            api::now(&mut now);
+            u64::from_le_bytes(now[..8].try_into().unwrap())
+        }
+
+        fn call_data_size(&self) -> usize {
+            api::call_data_size() as usize
+        }
+
+        fn call_data_copy(&self, output: &mut [u8]) {
+            api::call_data_copy(output, 0);
+        }
+
+        fn get_storage(&self, key: &[u8; 32], output: &mut [u8]) -> Option<usize> {
+            let mut output = output;
+            api::get_storage(StorageFlags::empty(), key, &mut output).ok()?;
+            Some(output.len())
+        }
+
+        fn set_storage(&mut self, key: &[u8; 32], value: &[u8]) {
+            api::set_storage(StorageFlags::empty(), key, value);
+        }
+
+        fn call(&mut self, callee: &Address, value: &[u8; 32], input: &[u8], output: &mut [u8]) -> Option<usize> {
+            let mut output = output;
+            api::call(
+                CallFlags::empty(), // No ALLOW_REENTRY: the callee cannot call back into us
+                callee,
+                u64::MAX,       // No ref_time limit beyond what the caller gave us
+                u64::MAX,       // No proof_size limit
+                &[u8::MAX; 32], // No deposit limit
+                value,
+                input,
+                Some(&mut output),
+            )
+            .ok()?;
+            Some(output.len())
+        }
+
+        fn deposit_event(&mut self, topics: &[[u8; 32]], data: &[u8]) {
+            api::deposit_event(topics, data);
+        }
+
+        fn set_code_hash(&mut self, code_hash: &[u8; 32]) {
+            // Traps (reverting the whole call) if no code with this hash was uploaded
+            api::set_code_hash(code_hash);
+        }
+
+        fn return_value(&mut self, revert: bool, data: &[u8]) {
+            let flags = if revert { ReturnFlags::REVERT } else { ReturnFlags::empty() };
+            api::return_value(flags, data)
+        }
+    }
+}
@@ -1,172 +1,502 @@
 // filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/job_management.rs
-#![cfg_attr(not(feature = "std"), no_std)]
-
-use crate::types::{Job, JobId, JobStatus, UserId, Balance, ContractError};
-use uapi::{HostFn, HostFnImpl as api};
-
-// For simplicity, we'll use a fixed-size array for storage.
-// A real contract would use pallet_contracts::storage or similar.
-const MAX_JOBS: usize = 100;
-static mut JOBS: [Option<Job>; MAX_JOBS] = [None; MAX_JOBS];
-static mut NEXT_JOB_ID: JobId = 0;
-
-fn find_job_index(job_id: JobId) -> Option<usize> {
-    unsafe {
-        for i in 0..MAX_JOBS {
-            if let Some(job) = &JOBS[i] {
-                if job.id == job_id {
-                    return Some(i);
-                }
-            }
-        }
-        None
-    }
-}
+
+use crate::types::{Job, JobId, JobParams, JobStatus, Address, Balance, ContractError};
+use crate::host::Host;
+use crate::storage;
+use crate::escrow;
+use crate::events;
+use crate::admin;
+use crate::pagination::{self, MAX_PAGE_SIZE};
+
+// Jobs live in contract storage (see storage.rs), so they survive across calls.
+// We still cap the number of jobs so ids stay within a known range.
+const MAX_JOBS: JobId = 100;
+
+/// listJobs status filter that matches every job.
+pub const ANY_STATUS: u8 = 0xff;
+
+/// How long after the bidding deadline the client has to pick a bid before anyone may expire the job.
+pub const BIDDING_GRACE_PERIOD: u64 = 7 * 24 * 60 * 60;
 
 // --- Public Functions (callable via contract ABI) ---
 
-/// Creates a new job.
-/// Input: client_id (UserId), budget (Balance)
+/// Creates a new job. Any value sent with the call is locked in escrow for it.
+/// Jobs paid in an ERC-20 token take no value; they are funded with fund_job_with_token.
+/// Input: client_id (Address, the caller), params (&JobParams), deposit (Balance, value transferred)
 /// Output: job_id (JobId) or error code
-pub fn create_job(client_id: UserId, budget: Balance) -> Result<JobId, ContractError> {
-    unsafe {
-        if NEXT_JOB_ID >= MAX_JOBS as JobId {
-            return Err(ContractError::StorageFull);
-        }
+pub fn create_job(host: &mut impl Host, client_id: Address, params: &JobParams, deposit: Balance) -> Result<JobId, ContractError> {
+    admin::ensure_not_paused(host)?;
+    if params.payment_token != escrow::NATIVE_TOKEN && deposit != 0 {
+        return Err(ContractError::InvalidInput); // Native value for a token job would be stuck
+    }
+    if params.bidding_deadline != 0 && params.bidding_deadline <= host.now() {
+        return Err(ContractError::InvalidInput); // Bidding would be closed from the start
+    }
+    if params.budget < admin::config(host).min_job_budget || params.min_budget > params.budget {
+        return Err(ContractError::InvalidInput);
+    }
+    let job_id = storage::next_job_id(host);
+    if job_id >= MAX_JOBS {
+        return Err(ContractError::StorageFull);
+    }
 
-        let job_id = NEXT_JOB_ID;
-        let new_job = Job {
-            id: job_id,
-            client_id,
-            budget,
-            status: JobStatus::Open,
-        };
-
-        // Find an empty slot (should be NEXT_JOB_ID if no deletions)
-        let mut stored = false;
-        for i in 0..MAX_JOBS {
-            if JOBS[i].is_none() { // Check if slot is empty
-                 JOBS[i] = Some(new_job);
-                 stored = true;
-                 break;
-            }
-        }
-        if !stored { // Should not happen if MAX_JOBS check is correct
-             return Err(ContractError::StorageFull);
-        }
+    let new_job = Job {
+        id: job_id,
+        client_id,
+        budget: params.budget,
+        status: JobStatus::Open,
+        content_cid: params.content_cid,
+        category: params.category,
+        deadline: params.deadline,
+        bidding_deadline: params.bidding_deadline,
+        payment_token: params.payment_token,
+        min_budget: params.min_budget,
+    };
+    storage::save_job(host, &new_job);
+    storage::set_next_job_id(host, job_id + 1);
+    escrow::lock(host, job_id, deposit)?;
+    events::job_created(host, &new_job);
+    Ok(job_id)
+}
+
+/// Tops up the escrow of an Open job paid in the native token. Only the job's client can
+/// fund it, since refunds always go back to the client.
+/// Input: caller (Address), job_id (JobId), deposit (Balance, value transferred)
+/// Output: new escrow balance or error code
+pub fn fund_job(host: &mut impl Host, caller: Address, job_id: JobId, deposit: Balance) -> Result<Balance, ContractError> {
+    let job = load_fundable_job(host, caller, job_id, deposit)?;
+    if job.payment_token != escrow::NATIVE_TOKEN {
+        return Err(ContractError::InvalidInput); // Wrong currency
+    }
+    escrow::lock(host, job_id, deposit)?;
+    let balance = storage::escrow_balance(host, job_id);
+    events::job_funded(host, job_id, deposit, balance);
+    Ok(balance)
+}
+
+/// Tops up the escrow of an Open job paid in an ERC-20 token by pulling `amount` from the
+/// client, who must have approved the contract first. Client only, like fund_job.
+/// Input: caller (Address), job_id (JobId), amount (Balance)
+/// Output: new escrow balance or error code
+pub fn fund_job_with_token(host: &mut impl Host, caller: Address, job_id: JobId, amount: Balance) -> Result<Balance, ContractError> {
+    let job = load_fundable_job(host, caller, job_id, amount)?;
+    if job.payment_token == escrow::NATIVE_TOKEN {
+        return Err(ContractError::InvalidInput); // Native jobs are funded with value
+    }
+    escrow::lock_tokens(host, job_id, &job.payment_token, &caller, amount)?;
+    let balance = storage::escrow_balance(host, job_id);
+    events::job_funded(host, job_id, amount, balance);
+    Ok(balance)
+}
 
-        NEXT_JOB_ID += 1;
-        Ok(job_id)
+fn load_fundable_job(host: &impl Host, caller: Address, job_id: JobId, amount: Balance) -> Result<Job, ContractError> {
+    admin::ensure_not_paused(host)?;
+    let job = storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
+    if caller != job.client_id {
+        return Err(ContractError::Unauthorized(caller));
+    }
+    if job.status != JobStatus::Open || amount == 0 {
+        return Err(ContractError::InvalidOperation);
     }
+    Ok(job)
 }
 
+/// Gets the amount currently held in escrow for a job.
+/// Input: job_id (JobId)
+/// Output: escrow balance or error code
+pub fn get_escrow_balance(host: &impl Host, job_id: JobId) -> Result<Balance, ContractError> {
+    storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
+    Ok(storage::escrow_balance(host, job_id))
+}
+
+/// getJob return values:
+/// (client_id, budget, status_u8, content_cid, category, deadline, bidding_deadline, payment_token, min_budget)
+pub type JobView = (Address, Balance, u8, [u8; 32], u32, u64, u64, Address, Balance);
+
 /// Gets job details.
 /// Input: job_id (JobId)
-/// Output: (client_id, budget, status_u8) or error code
-pub fn get_job(job_id: JobId) -> Result<(UserId, Balance, u8), ContractError> {
-    unsafe {
-        if let Some(index) = find_job_index(job_id) {
-            if let Some(job) = &JOBS[index] {
-                return Ok((job.client_id, job.budget, job.status as u8));
-            }
-        }
-        Err(ContractError::NotFound)
-    }
+/// Output: JobView or error code
+pub fn get_job(host: &impl Host, job_id: JobId) -> Result<JobView, ContractError> {
+    let job = storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
+    Ok((
+        job.client_id,
+        job.budget,
+        job.status as u8,
+        job.content_cid,
+        job.category,
+        job.deadline,
+        job.bidding_deadline,
+        job.payment_token,
+        job.min_budget,
+    ))
+}
+
+/// Lists job ids, optionally only those in one status.
+/// Input: offset (u32), limit (u32), status_filter (u8, ANY_STATUS for all), page buffer
+/// Output: (total matching jobs, number of ids written to `page`) or error code
+pub fn list_jobs(host: &impl Host, offset: u32, limit: u32, status_filter: u8, page: &mut [JobId; MAX_PAGE_SIZE]) -> Result<(u32, usize), ContractError> {
+    let filter = match status_filter {
+        ANY_STATUS => None,
+        status_u8 => Some(JobStatus::from_u8(status_u8).ok_or(ContractError::InvalidInput)?),
+    };
+    let matches = (0..storage::next_job_id(host)).filter(|job_id| match storage::load_job(host, *job_id) {
+        Some(job) => filter.is_none_or(|status| job.status == status),
+        None => false,
+    });
+    Ok(pagination::paginate(matches, offset, limit, page))
 }
 
-/// Updates a job's status.
-/// Input: job_id (JobId), new_status_u8 (u8)
+/// Updates a job's status on behalf of `caller`.
+/// Input: caller (Address), job_id (JobId), new_status_u8 (u8)
 /// Output: 0 on success or error code
-pub fn update_job_status(job_id: JobId, new_status_u8: u8) -> Result<(), ContractError> {
-    unsafe {
-        let new_status = JobStatus::from_u8(new_status_u8).ok_or(ContractError::InvalidInput)?;
-
-        if let Some(index) = find_job_index(job_id) {
-            if let Some(job) = &mut JOBS[index] {
-                // Basic state transition validation (can be expanded)
-                match (job.status, new_status) {
-                    (JobStatus::Open, JobStatus::InProgress) => {},
-                    (JobStatus::Open, JobStatus::Cancelled) => {},
-                    (JobStatus::InProgress, JobStatus::Completed) => {},
-                    (JobStatus::InProgress, JobStatus::Cancelled) => {},
-                    _ => return Err(ContractError::InvalidOperation),
-                }
-                job.status = new_status;
-                return Ok(());
-            }
-        }
-        Err(ContractError::NotFound)
+pub fn update_job_status(host: &mut impl Host, caller: Address, job_id: JobId, new_status_u8: u8) -> Result<(), ContractError> {
+    let new_status = JobStatus::from_u8(new_status_u8).ok_or(ContractError::InvalidInput)?;
+    if new_status != JobStatus::Cancelled {
+        admin::ensure_not_paused(host)?; // Cancelling stays open so clients can get their escrow back
     }
-}
+    let job = storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
 
-// --- Helper for ABI encoding/decoding (simplified) ---
+    check_job_transition(job.status, new_status)?;
+    ensure_can_update_job(&job, &caller, &storage::owner(host), new_status)?;
+    let client_id = job.client_id;
+    store_job_status(host, job, new_status);
 
-/// Decodes create_job arguments: client_id (u32), budget (u128)
-pub fn decode_create_job_args(data: &[u8]) -> Result<(UserId, Balance), ContractError> {
-    if data.len() < 4 + 16 { // selector (4) + client_id (4) + budget (16)
-        return Err(ContractError::InvalidInput);
+    if new_status == JobStatus::Cancelled {
+        // Cancelled jobs hand the whole escrow back to the client
+        escrow::refund(host, job_id, &client_id)?;
     }
-    let client_id = u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?);
-    let budget = u128::from_be_bytes(data[4..20].try_into().map_err(|_| ContractError::InvalidInput)?);
-    Ok((client_id, budget))
+    Ok(())
 }
 
-/// Decodes get_job arguments: job_id (u32)
-pub fn decode_get_job_args(data: &[u8]) -> Result<JobId, ContractError> {
-    if data.len() < 4 { // selector (4) + job_id (4)
-        return Err(ContractError::InvalidInput);
+/// Cancels an Open job whose delivery deadline has passed, or whose bidding deadline passed more
+/// than BIDDING_GRACE_PERIOD ago, and refunds its escrow. Anyone may call this, so stale jobs don't
+/// depend on the client to clean them up; the grace period leaves the client time to accept a bid.
+/// Input: job_id (JobId)
+/// Output: 0 on success or error code
+pub fn expire_job(host: &mut impl Host, job_id: JobId) -> Result<(), ContractError> {
+    let job = storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
+    if job.status != JobStatus::Open || !is_stale(&job, host.now()) {
+        return Err(ContractError::InvalidOperation);
     }
-    Ok(u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?))
+    let client_id = job.client_id;
+    store_job_status(host, job, JobStatus::Cancelled);
+    escrow::refund(host, job_id, &client_id)
 }
 
-/// Decodes update_job_status arguments: job_id (u32), new_status (u8)
-pub fn decode_update_job_status_args(data: &[u8]) -> Result<(JobId, u8), ContractError> {
-    if data.len() < 4 + 1 { // selector (4) + job_id (4) + status (1)
-        return Err(ContractError::InvalidInput);
+/// A job is stale once its delivery deadline (if set) lies in the past, or its bidding deadline
+/// (if set) plus the grace period.
+fn is_stale(job: &Job, now: u64) -> bool {
+    let passed = |deadline: u64, grace: u64| deadline != 0 && now > deadline.saturating_add(grace);
+    passed(job.bidding_deadline, BIDDING_GRACE_PERIOD) || passed(job.deadline, 0)
+}
+
+/// Moves a job to `new_status` as part of the agreement lifecycle.
+/// Only the transition rules apply here; the calling module has already authorized the action.
+pub fn set_job_status(host: &mut impl Host, job_id: JobId, new_status: JobStatus) -> Result<(), ContractError> {
+    let job = storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
+    check_job_transition(job.status, new_status)?;
+    store_job_status(host, job, new_status);
+    Ok(())
+}
+
+fn store_job_status(host: &mut impl Host, mut job: Job, new_status: JobStatus) {
+    let old_status = job.status;
+    job.status = new_status;
+    storage::save_job(host, &job);
+    events::job_status_changed(host, job.id, old_status as u8, new_status as u8);
+}
+
+// --- Transition rules ---
+
+fn check_job_transition(from: JobStatus, to: JobStatus) -> Result<(), ContractError> {
+    // Basic state transition validation (can be expanded)
+    match (from, to) {
+        (JobStatus::Open, JobStatus::InProgress) => Ok(()),
+        (JobStatus::Open, JobStatus::Cancelled) => Ok(()),
+        (JobStatus::InProgress, JobStatus::Completed) => Ok(()),
+        (JobStatus::InProgress, JobStatus::Cancelled) => Ok(()),
+        _ => Err(ContractError::InvalidTransition(from as u8, to as u8)),
     }
-    let job_id = u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?);
-    let status = data[4];
-    Ok((job_id, status))
 }
 
-/// Encodes a JobId result
-pub fn encode_job_id_result(result: Result<JobId, ContractError>, output_buffer: &mut [u8; 32]) {
-    match result {
-        Ok(job_id) => {
-            output_buffer[28..].copy_from_slice(&job_id.to_be_bytes());
-        }
-        Err(err) => {
-            output_buffer[31] = err as u8; // Simplified error reporting
-        }
+/// Checks that `caller` may move `job` to `new_status` directly.
+/// - The job's client, or the owner, may cancel it while it is still Open.
+/// - Everything else (starting, completing, cancelling work in progress) follows from the
+///   agreement, so that its milestones and the escrow settle together. Work in progress is
+///   cancelled by resolving a dispute with a full refund.
+fn ensure_can_update_job(job: &Job, caller: &Address, owner: &Address, new_status: JobStatus) -> Result<(), ContractError> {
+    match (job.status, new_status) {
+        (JobStatus::Open, JobStatus::Cancelled) if *caller == job.client_id || caller == owner => Ok(()),
+        _ => Err(ContractError::Unauthorized(*caller)),
     }
 }
 
-/// Encodes a GetJob result
-pub fn encode_get_job_result(result: Result<(UserId, Balance, u8), ContractError>, output_buffer: &mut [u8; 32]) {
-    match result {
-        Ok((client_id, budget, status_u8)) => {
-            let mut temp_buf = [0u8; 4 + 16 + 1];
-            temp_buf[0..4].copy_from_slice(&client_id.to_be_bytes());
-            temp_buf[4..20].copy_from_slice(&budget.to_be_bytes());
-            temp_buf[20] = status_u8;
-            // Right-align in the 32-byte buffer
-            let offset = 32 - temp_buf.len();
-            output_buffer[offset..].copy_from_slice(&temp_buf);
-        }
-        Err(err) => {
-            output_buffer[31] = err as u8;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{job_params, MockHost, MockToken, CONTRACT};
+    use crate::abi::Word;
+
+    const CLIENT: Address = [1u8; 20];
+    const OWNER: Address = [9u8; 20];
+    const STRANGER: Address = [7u8; 20];
+
+    fn deployed() -> MockHost {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        host
+    }
+
+    fn job(status: JobStatus) -> Job {
+        Job {
+            id: 0,
+            client_id: CLIENT,
+            budget: 1_000,
+            status,
+            content_cid: [0u8; 32],
+            category: 0,
+            deadline: 0,
+            bidding_deadline: 0,
+            payment_token: escrow::NATIVE_TOKEN,
+            min_budget: 0,
         }
     }
-}
 
-/// Encodes a simple success/failure result (e.g., for updates)
-pub fn encode_simple_result(result: Result<(), ContractError>, output_buffer: &mut [u8; 32]) {
-    match result {
-        Ok(_) => {
-            // output_buffer remains zero for success, or set a specific success code if desired
+    fn allowed(status: JobStatus, caller: Address, new_status: JobStatus) -> bool {
+        ensure_can_update_job(&job(status), &caller, &OWNER, new_status).is_ok()
+    }
+
+    #[test]
+    fn only_client_or_owner_can_cancel_open_job() {
+        assert!(allowed(JobStatus::Open, CLIENT, JobStatus::Cancelled));
+        assert!(allowed(JobStatus::Open, OWNER, JobStatus::Cancelled));
+        assert!(!allowed(JobStatus::Open, STRANGER, JobStatus::Cancelled));
+    }
+
+    #[test]
+    fn client_cannot_drive_agreement_transitions() {
+        assert!(!allowed(JobStatus::Open, CLIENT, JobStatus::InProgress));
+        assert!(!allowed(JobStatus::InProgress, CLIENT, JobStatus::Completed));
+        assert!(!allowed(JobStatus::InProgress, CLIENT, JobStatus::Cancelled));
+    }
+
+    #[test]
+    fn owner_cannot_drive_agreement_transitions() {
+        assert!(!allowed(JobStatus::Open, OWNER, JobStatus::InProgress));
+        assert!(!allowed(JobStatus::InProgress, OWNER, JobStatus::Completed));
+        assert!(!allowed(JobStatus::InProgress, OWNER, JobStatus::Cancelled));
+    }
+
+    #[test]
+    fn invalid_transitions_are_rejected() {
+        assert!(check_job_transition(JobStatus::Completed, JobStatus::Open).is_err());
+        assert!(check_job_transition(JobStatus::Cancelled, JobStatus::InProgress).is_err());
+        assert!(check_job_transition(JobStatus::Open, JobStatus::Completed).is_err());
+    }
+
+    #[test]
+    fn jobs_persist_with_sequential_ids() {
+        let mut host = deployed();
+        assert_eq!(create_job(&mut host, CLIENT, &job_params(1_000), 0), Ok(0));
+        assert_eq!(create_job(&mut host, STRANGER, &job_params(2_000), 0), Ok(1));
+
+        assert_eq!(get_job(&host, 0).unwrap().0, CLIENT);
+        assert_eq!(get_job(&host, 1).unwrap().0, STRANGER);
+        assert_eq!(get_job(&host, 1).unwrap().1, 2_000);
+        assert_eq!(get_job(&host, 2), Err(ContractError::NotFound(2)));
+    }
+
+    #[test]
+    fn create_job_stores_metadata() {
+        let mut host = deployed();
+        let params = JobParams { budget: 1_000, content_cid: [0xcd; 32], category: 7, deadline: 1_700_000_000, ..JobParams::default() };
+        let job_id = create_job(&mut host, CLIENT, &params, 0).unwrap();
+
+        let expected = (CLIENT, 1_000, JobStatus::Open as u8, [0xcd; 32], 7, 1_700_000_000, 0, escrow::NATIVE_TOKEN, 0);
+        assert_eq!(get_job(&host, job_id), Ok(expected));
+    }
+
+    #[test]
+    fn create_job_locks_deposit_and_emits_event() {
+        let mut host = deployed();
+        let params = JobParams { budget: 1_000, content_cid: [0xcd; 32], category: 7, deadline: 1_700_000_000, ..JobParams::default() };
+        let job_id = create_job(&mut host, CLIENT, &params, 600).unwrap();
+
+        assert_eq!(get_escrow_balance(&host, job_id), Ok(600));
+        let event = host.events.last().unwrap();
+        assert_eq!(event.topics[0], events::JOB_CREATED);
+        assert_eq!(event.topics[2][12..], CLIENT);
+        // budget, contentCid, category, deadline
+        assert_eq!(event.data[..32], 1_000u128.to_word());
+        assert_eq!(event.data[32..64], [0xcd; 32]);
+        assert_eq!(event.data[64..96], 7u32.to_word());
+        assert_eq!(event.data[96..], 1_700_000_000u64.to_word());
+    }
+
+    #[test]
+    fn create_job_fails_when_storage_is_full() {
+        let mut host = deployed();
+        for _ in 0..MAX_JOBS {
+            create_job(&mut host, CLIENT, &job_params(1), 0).unwrap();
         }
-        Err(err) => {
-            output_buffer[31] = err as u8;
+        assert_eq!(create_job(&mut host, CLIENT, &job_params(1), 0), Err(ContractError::StorageFull));
+    }
+
+    #[test]
+    fn only_client_can_fund_open_job() {
+        let mut host = deployed();
+        let job_id = create_job(&mut host, CLIENT, &job_params(1_000), 600).unwrap();
+
+        assert_eq!(fund_job(&mut host, STRANGER, job_id, 400), Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(fund_job(&mut host, CLIENT, job_id, 400), Ok(1_000));
+        assert_eq!(fund_job(&mut host, CLIENT, job_id, 0), Err(ContractError::InvalidOperation));
+    }
+
+    #[test]
+    fn token_job_is_funded_by_transfer_from_and_refunded_in_tokens() {
+        const TOKEN: Address = [0x70; 20];
+        let mut host = deployed();
+        let mut token = MockToken::default();
+        token.balances.insert(CLIENT, 1_000);
+        token.allowances.insert((CLIENT, CONTRACT), 600);
+        host.tokens.insert(TOKEN, token);
+
+        let params = JobParams { budget: 1_000, payment_token: TOKEN, ..JobParams::default() };
+        assert_eq!(create_job(&mut host, CLIENT, &params, 1), Err(ContractError::InvalidInput));
+        let job_id = create_job(&mut host, CLIENT, &params, 0).unwrap();
+        assert_eq!(host.events.last().unwrap().topics[3][12..], TOKEN);
+
+        assert_eq!(fund_job(&mut host, CLIENT, job_id, 600), Err(ContractError::InvalidInput));
+        assert_eq!(fund_job_with_token(&mut host, CLIENT, job_id, 700), Err(ContractError::TransferFailed(CONTRACT, 700)));
+        assert_eq!(fund_job_with_token(&mut host, CLIENT, job_id, 600), Ok(600));
+        assert_eq!(host.token_balance(&TOKEN, &CONTRACT), 600);
+
+        let native_job = create_job(&mut host, CLIENT, &job_params(1_000), 0).unwrap();
+        assert_eq!(fund_job_with_token(&mut host, CLIENT, native_job, 600), Err(ContractError::InvalidInput));
+
+        update_job_status(&mut host, CLIENT, job_id, JobStatus::Cancelled as u8).unwrap();
+        assert_eq!(host.token_balance(&TOKEN, &CLIENT), 1_000);
+        assert!(host.transfers.is_empty());
+    }
+
+    #[test]
+    fn cancelling_open_job_refunds_client() {
+        let mut host = deployed();
+        let job_id = create_job(&mut host, CLIENT, &job_params(1_000), 1_000).unwrap();
+
+        update_job_status(&mut host, CLIENT, job_id, JobStatus::Cancelled as u8).unwrap();
+        assert_eq!(get_job(&host, job_id).unwrap().2, JobStatus::Cancelled as u8);
+        assert_eq!(get_escrow_balance(&host, job_id), Ok(0));
+        assert_eq!(host.transfers, vec![(CLIENT, 1_000)]);
+    }
+
+    #[test]
+    fn stranger_cannot_cancel_job() {
+        let mut host = deployed();
+        let job_id = create_job(&mut host, CLIENT, &job_params(1_000), 1_000).unwrap();
+
+        let result = update_job_status(&mut host, STRANGER, job_id, JobStatus::Cancelled as u8);
+        assert_eq!(result, Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(get_escrow_balance(&host, job_id), Ok(1_000));
+        assert!(host.transfers.is_empty());
+    }
+
+    #[test]
+    fn update_job_status_rejects_unknown_status_and_job() {
+        let mut host = deployed();
+        let job_id = create_job(&mut host, CLIENT, &job_params(1_000), 0).unwrap();
+
+        assert_eq!(update_job_status(&mut host, CLIENT, job_id, 42), Err(ContractError::InvalidInput));
+        assert_eq!(update_job_status(&mut host, CLIENT, 5, JobStatus::Cancelled as u8), Err(ContractError::NotFound(5)));
+    }
+
+    #[test]
+    fn failed_refund_is_reported() {
+        let mut host = deployed();
+        host.failing_callees.push(CLIENT);
+        let job_id = create_job(&mut host, CLIENT, &job_params(1_000), 1_000).unwrap();
+
+        let result = update_job_status(&mut host, CLIENT, job_id, JobStatus::Cancelled as u8);
+        assert_eq!(result, Err(ContractError::TransferFailed(CLIENT, 1_000)));
+    }
+
+    #[test]
+    fn list_jobs_filters_by_status() {
+        let mut host = deployed();
+        for _ in 0..4 {
+            create_job(&mut host, CLIENT, &job_params(1_000), 0).unwrap();
         }
+        update_job_status(&mut host, CLIENT, 1, JobStatus::Cancelled as u8).unwrap();
+        let mut page = [0; MAX_PAGE_SIZE];
+
+        assert_eq!(list_jobs(&host, 0, 10, ANY_STATUS, &mut page), Ok((4, 4)));
+        assert_eq!(page[..4], [0, 1, 2, 3]);
+        assert_eq!(list_jobs(&host, 1, 1, JobStatus::Open as u8, &mut page), Ok((3, 1)));
+        assert_eq!(page[0], 2);
+        assert_eq!(list_jobs(&host, 0, 10, JobStatus::Cancelled as u8, &mut page), Ok((1, 1)));
+        assert_eq!(page[0], 1);
+        assert_eq!(list_jobs(&host, 0, 10, 4, &mut page), Err(ContractError::InvalidInput));
+    }
+
+    fn with_deadlines(host: &mut MockHost, bidding_deadline: u64, deadline: u64) -> JobId {
+        let params = JobParams { budget: 1_000, bidding_deadline, deadline, ..JobParams::default() };
+        create_job(host, CLIENT, &params, 1_000).unwrap()
+    }
+
+    #[test]
+    fn budget_range_must_be_ordered() {
+        let mut host = deployed();
+        let params = JobParams { budget: 1_000, min_budget: 1_001, ..JobParams::default() };
+        assert_eq!(create_job(&mut host, CLIENT, &params, 0), Err(ContractError::InvalidInput));
+
+        let params = JobParams { budget: 1_000, min_budget: 1_000, ..JobParams::default() };
+        let job_id = create_job(&mut host, CLIENT, &params, 0).unwrap();
+        assert_eq!(get_job(&host, job_id).unwrap().8, 1_000);
+    }
+
+    #[test]
+    fn bidding_deadline_must_be_in_the_future() {
+        let mut host = deployed();
+        host.timestamp = 1_000;
+        let params = JobParams { budget: 1_000, bidding_deadline: 1_000, ..JobParams::default() };
+        assert_eq!(create_job(&mut host, CLIENT, &params, 0), Err(ContractError::InvalidInput));
+    }
+
+    #[test]
+    fn anyone_can_expire_stale_open_job() {
+        let mut host = deployed();
+        host.timestamp = 1_000;
+        let by_bidding = with_deadlines(&mut host, 2_000, 0);
+        let by_delivery = with_deadlines(&mut host, 0, 3_000);
+
+        // Closing the bids starts the client's grace period to pick one
+        host.timestamp = 2_001;
+        assert_eq!(expire_job(&mut host, by_bidding), Err(ContractError::InvalidOperation));
+        assert_eq!(expire_job(&mut host, by_delivery), Err(ContractError::InvalidOperation));
+        host.timestamp = 3_001;
+        expire_job(&mut host, by_delivery).unwrap();
+        host.timestamp = 2_000 + BIDDING_GRACE_PERIOD;
+        assert_eq!(expire_job(&mut host, by_bidding), Err(ContractError::InvalidOperation));
+        host.timestamp += 1;
+        expire_job(&mut host, by_bidding).unwrap();
+
+        assert_eq!(get_job(&host, by_bidding).unwrap().2, JobStatus::Cancelled as u8);
+        assert_eq!(get_job(&host, by_delivery).unwrap().2, JobStatus::Cancelled as u8);
+        assert_eq!(host.transfers, vec![(CLIENT, 1_000), (CLIENT, 1_000)]);
+    }
+
+    #[test]
+    fn expire_job_leaves_fresh_and_started_jobs_alone() {
+        let mut host = deployed();
+        host.timestamp = 1_000;
+        let no_deadline = with_deadlines(&mut host, 0, 0);
+        let started = with_deadlines(&mut host, 2_000, 0);
+        set_job_status(&mut host, started, JobStatus::InProgress).unwrap();
+
+        host.timestamp = 10_000;
+        assert_eq!(expire_job(&mut host, no_deadline), Err(ContractError::InvalidOperation));
+        assert_eq!(expire_job(&mut host, started), Err(ContractError::InvalidOperation));
+        assert_eq!(expire_job(&mut host, 9), Err(ContractError::NotFound(9)));
+        assert!(host.transfers.is_empty());
     }
 }
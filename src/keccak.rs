@@ -0,0 +1,142 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/keccak.rs
+
+// Minimal Keccak-256 (the original Keccak padding used by Ethereum, not SHA3-256).
+// Everything is a `const fn` so selectors and event signatures are computed by the
+// compiler and end up as plain constants in the contract blob.
+
+const RATE: usize = 136; // 1088-bit rate for a 256-bit output
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+const ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI_LANES: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+const fn keccak_f(mut state: [u64; 25]) -> [u64; 25] {
+    let mut round = 0;
+    while round < 24 {
+        // Theta
+        let mut c = [0u64; 5];
+        let mut x = 0;
+        while x < 5 {
+            c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+            x += 1;
+        }
+        x = 0;
+        while x < 5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            let mut y = 0;
+            while y < 25 {
+                state[y + x] ^= d;
+                y += 5;
+            }
+            x += 1;
+        }
+
+        // Rho and Pi
+        let mut last = state[1];
+        let mut i = 0;
+        while i < 24 {
+            let lane = PI_LANES[i];
+            let tmp = state[lane];
+            state[lane] = last.rotate_left(ROTATIONS[i]);
+            last = tmp;
+            i += 1;
+        }
+
+        // Chi
+        let mut y = 0;
+        while y < 25 {
+            let row = [state[y], state[y + 1], state[y + 2], state[y + 3], state[y + 4]];
+            x = 0;
+            while x < 5 {
+                state[y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+                x += 1;
+            }
+            y += 5;
+        }
+
+        // Iota
+        state[0] ^= ROUND_CONSTANTS[round];
+        round += 1;
+    }
+    state
+}
+
+const fn absorb_byte(mut state: [u64; 25], pos: usize, byte: u8) -> [u64; 25] {
+    state[pos / 8] ^= (byte as u64) << (8 * (pos % 8));
+    state
+}
+
+/// Keccak-256 hash of `input`.
+pub const fn keccak256(input: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+    let mut pos = 0;
+    let mut i = 0;
+    while i < input.len() {
+        state = absorb_byte(state, pos, input[i]);
+        pos += 1;
+        if pos == RATE {
+            state = keccak_f(state);
+            pos = 0;
+        }
+        i += 1;
+    }
+
+    // Keccak padding: 0x01 ... 0x80
+    state = absorb_byte(state, pos, 0x01);
+    state = absorb_byte(state, RATE - 1, 0x80);
+    state = keccak_f(state);
+
+    let mut out = [0u8; 32];
+    let mut j = 0;
+    while j < 32 {
+        out[j] = (state[j / 8] >> (8 * (j % 8))) as u8;
+        j += 1;
+    }
+    out
+}
+
+/// Solidity function selector: the first 4 bytes of keccak256(signature), big-endian.
+/// Example: selector("createJob(uint32,uint128)")
+pub const fn selector(signature: &str) -> u32 {
+    let hash = keccak256(signature.as_bytes());
+    u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_the_empty_input() {
+        let expected = [
+            0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+            0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+        ];
+        assert_eq!(keccak256(b""), expected);
+    }
+
+    #[test]
+    fn matches_known_erc20_selectors() {
+        assert_eq!(selector("transfer(address,uint256)"), 0xa9059cbb);
+        assert_eq!(selector("transferFrom(address,address,uint256)"), 0x23b872dd);
+    }
+
+    #[test]
+    fn selectors_are_compile_time_constants() {
+        const TRANSFER: u32 = selector("transfer(address,uint256)");
+        assert_eq!(TRANSFER, 0xa9059cbb);
+    }
+}
@@ -1,15 +1,33 @@
-#![no_main]
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+// The std build only exists to run unit tests against mock::MockHost
+#![cfg_attr(feature = "std", allow(dead_code))]
 
 // Declare modules
 mod types;
 mod job_management;
 mod proposal_management;
 mod agreement_management;
+mod storage;
+mod keccak;
+mod abi;
+mod escrow;
+mod events;
+mod errors;
+mod host;
+mod reputation;
+mod pagination;
+mod admin;
+mod upgrade;
+mod erc20;
+#[cfg(feature = "std")]
+mod mock;
 
-use uapi::{HostFn, HostFnImpl as api, ReturnFlags};
-use types::ContractError;
+use types::{Address, Balance, Config, ContractError, JobParams};
+use abi::{Decoder, Encoder};
+use host::Host;
 
+#[cfg(not(feature = "std"))]
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
     unsafe {
@@ -18,161 +36,774 @@ fn panic(_info: &core::panic::PanicInfo) -> ! {
     }
 }
 
+// The host-side (`std`) build is only used for tests; it has no entry points.
+#[cfg(feature = "std")]
+fn main() {}
+
 /// Constructor - called once per contract.
+#[cfg(not(feature = "std"))]
 #[no_mangle]
 #[polkavm_derive::polkavm_export]
 pub extern "C" fn deploy() {
-    // Initialization logic for the Web3Lancer contract can go here.
-    // For now, static arrays in modules are initialized at compile time.
+    constructor(&mut host::ApiHost);
+}
+
+fn constructor(host: &mut impl Host) {
+    // Stores the owner (the deployer unless the input names someone else) and the initial config,
+    // see admin::init, and marks the empty storage as already in the current layout.
+    // Storage counters start at zero when unset, so nothing else needs writing yet.
+    const MAX_INPUT_LEN: usize = 256; // Max expected input size
+    let mut input_buffer = [0u8; MAX_INPUT_LEN];
+    let input_len = host.call_data_size();
+    if input_len > MAX_INPUT_LEN {
+        revert(host, ContractError::InvalidInput);
+        return;
+    }
+    let input = &mut input_buffer[..input_len];
+    host.call_data_copy(input);
+
+    let deployer = host.caller();
+    if let Err(e) = admin::init(host, deployer, input) {
+        revert(host, e);
+        return;
+    }
+    storage::set_storage_version(host, storage::STORAGE_VERSION);
 }
 
+// Selectors are the first 4 bytes of the keccak256 hash of the function signature,
+// matching the IWeb3LancerPolkadot interface in call_from_sol.sol.
+// They are evaluated at compile time, so each one is just a u32 constant.
+
+// Job Management Selectors
+const CREATE_JOB_SELECTOR: u32 = keccak::selector("createJob(uint128,bytes32,uint32,uint64,uint64,address,uint128)"); // payable
+const GET_JOB_SELECTOR: u32 = keccak::selector("getJob(uint32)");
+const UPDATE_JOB_STATUS_SELECTOR: u32 = keccak::selector("updateJobStatus(uint32,uint8)");
+const FUND_JOB_SELECTOR: u32 = keccak::selector("fundJob(uint32)"); // payable
+const FUND_JOB_WITH_TOKEN_SELECTOR: u32 = keccak::selector("fundJobWithToken(uint32,uint128)");
+const GET_ESCROW_BALANCE_SELECTOR: u32 = keccak::selector("getEscrowBalance(uint32)");
+const LIST_JOBS_SELECTOR: u32 = keccak::selector("listJobs(uint32,uint32,uint8)");
+const EXPIRE_JOB_SELECTOR: u32 = keccak::selector("expireJob(uint32)");
+
+// Proposal Management Selectors
+const SUBMIT_PROPOSAL_SELECTOR: u32 = keccak::selector("submitProposal(uint32,uint128,uint64,bytes32)");
+const GET_PROPOSAL_SELECTOR: u32 = keccak::selector("getProposal(uint32)");
+const UPDATE_PROPOSAL_STATUS_SELECTOR: u32 = keccak::selector("updateProposalStatus(uint32,uint8)");
+const UPDATE_PROPOSAL_SELECTOR: u32 = keccak::selector("updateProposal(uint32,uint128,bytes32)");
+const WITHDRAW_PROPOSAL_SELECTOR: u32 = keccak::selector("withdrawProposal(uint32)");
+const COUNTER_OFFER_SELECTOR: u32 = keccak::selector("counterOffer(uint32,uint128,bytes32)");
+const GET_NEGOTIATION_SELECTOR: u32 = keccak::selector("getNegotiation(uint32)");
+const GET_COUNTER_OFFER_SELECTOR: u32 = keccak::selector("getCounterOffer(uint32,uint32)");
+const LIST_PROPOSALS_FOR_JOB_SELECTOR: u32 = keccak::selector("listProposalsForJob(uint32,uint32,uint32)");
+const GET_PROPOSAL_BY_FREELANCER_SELECTOR: u32 = keccak::selector("getProposalByFreelancer(uint32,address)");
+
+// Agreement Management Selectors
+const CREATE_AGREEMENT_SELECTOR: u32 = keccak::selector("createAgreementFromProposal(uint32,uint128[],bytes32[])");
+const ACCEPT_PROPOSAL_SELECTOR: u32 = keccak::selector("acceptProposal(uint32,uint128[],bytes32[])"); // payable
+const GET_AGREEMENT_SELECTOR: u32 = keccak::selector("getAgreement(uint32)");
+const UPDATE_AGREEMENT_STATUS_SELECTOR: u32 = keccak::selector("updateAgreementStatus(uint32,uint8)");
+const LIST_AGREEMENTS_FOR_USER_SELECTOR: u32 = keccak::selector("listAgreementsForUser(address,uint32,uint32)");
+const GET_MILESTONE_SELECTOR: u32 = keccak::selector("getMilestone(uint32,uint32)");
+const SUBMIT_MILESTONE_SELECTOR: u32 = keccak::selector("submitMilestone(uint32,uint32)");
+const APPROVE_MILESTONE_SELECTOR: u32 = keccak::selector("approveMilestone(uint32,uint32)");
+const RELEASE_MILESTONE_SELECTOR: u32 = keccak::selector("releaseMilestone(uint32,uint32)");
+const RESOLVE_DISPUTE_SELECTOR: u32 = keccak::selector("resolveDispute(uint32,uint16)");
+const GET_PAYOUT_BREAKDOWN_SELECTOR: u32 = keccak::selector("getPayoutBreakdown(uint32)");
+
+// Reputation Selectors
+const RATE_COUNTERPARTY_SELECTOR: u32 = keccak::selector("rateCounterparty(uint32,uint8,bytes32)");
+const GET_REPUTATION_SELECTOR: u32 = keccak::selector("getReputation(address)");
+const GET_REVIEW_SELECTOR: u32 = keccak::selector("getReview(uint32,address)");
+
+// Admin Selectors
+const GET_CONFIG_SELECTOR: u32 = keccak::selector("getConfig()");
+const SET_CONFIG_SELECTOR: u32 = keccak::selector("setConfig(address,uint16,uint128,uint32)");
+const TRANSFER_OWNERSHIP_SELECTOR: u32 = keccak::selector("transferOwnership(address)");
+const ACCEPT_OWNERSHIP_SELECTOR: u32 = keccak::selector("acceptOwnership()");
+const PAUSE_SELECTOR: u32 = keccak::selector("pause()");
+const UNPAUSE_SELECTOR: u32 = keccak::selector("unpause()");
+const PAUSED_SELECTOR: u32 = keccak::selector("paused()");
+const WITHDRAW_FEES_SELECTOR: u32 = keccak::selector("withdrawFees(address,uint128)");
+const GET_TREASURY_BALANCE_SELECTOR: u32 = keccak::selector("getTreasuryBalance()");
+const WITHDRAW_TOKEN_FEES_SELECTOR: u32 = keccak::selector("withdrawTokenFees(address,address,uint128)");
+const GET_TOKEN_TREASURY_BALANCE_SELECTOR: u32 = keccak::selector("getTokenTreasuryBalance(address)");
+
+// Upgrade Selectors
+const UPGRADE_SELECTOR: u32 = keccak::selector("upgrade(bytes32)");
+const MIGRATE_SELECTOR: u32 = keccak::selector("migrate(uint32)");
+const GET_STORAGE_VERSION_SELECTOR: u32 = keccak::selector("getStorageVersion()");
+
 /// Main entry point when the contract is called.
-/// Handles dispatching to different contract functionalities.
+#[cfg(not(feature = "std"))]
 #[no_mangle]
 #[polkavm_derive::polkavm_export]
 pub extern "C" fn call() {
-    let mut input_buffer = [0u8; 256]; // Max expected input size
-    let call_data_len = api::read_call_data(&mut input_buffer, 0);
-    let call_data = &input_buffer[..call_data_len as usize];
+    execute(&mut host::ApiHost);
+}
+
+/// Reads the call data, dispatches it and hands the result (or revert data) back to the host.
+fn execute(host: &mut impl Host) {
+    const MAX_INPUT_LEN: usize = 2048; // Max expected input size (a full milestone schedule is ~1.2 KiB)
+    let mut input_buffer = [0u8; MAX_INPUT_LEN];
+    let call_data_len = host.call_data_size();
 
-    if call_data.len() < 4 {
-        // Not enough data for a selector
-        api::return_value(ReturnFlags::REVERT, &[]);
+    if !(4..=MAX_INPUT_LEN).contains(&call_data_len) {
+        // Not enough data for a selector, or more than we can hold
+        host.return_value(true, &[]);
         return;
     }
+    let call_data = &mut input_buffer[..call_data_len];
+    host.call_data_copy(call_data);
 
     let selector = u32::from_be_bytes(call_data[0..4].try_into().unwrap());
     let args = &call_data[4..];
-    let mut output_buffer = [0u8; 32]; // Standard 32-byte output for single values or errors
+    let mut output_buffer = [0u8; 2048]; // ABI-encoded return tuple (a full list page is ~1.7 KiB)
 
-    // Dispatch based on selector
-    // Selectors are the first 4 bytes of the keccak256 hash of the function signature.
-    // Example: "createJob(uint32,uint128)" -> keccak256 -> first 4 bytes
-    // For simplicity, we'll use hardcoded placeholder selectors.
-    // In a real scenario, generate these properly.
+    let caller = host.caller();
+    match dispatch(host, caller, selector, args, &mut output_buffer) {
+        Ok(len) => host.return_value(false, &output_buffer[..len]),
+        Err(e) => revert(host, e),
+    }
+}
 
-    // Job Management Selectors (Placeholder values)
-    const CREATE_JOB_SELECTOR: u32 = 0x00000001;
-    const GET_JOB_SELECTOR: u32 = 0x00000002;
-    const UPDATE_JOB_STATUS_SELECTOR: u32 = 0x00000003;
+/// Reverts with the Solidity custom error matching `e`.
+fn revert(host: &mut impl Host, e: ContractError) {
+    let mut revert_data = [0u8; errors::MAX_REVERT_LEN];
+    let len = e.encode(&mut revert_data);
+    host.return_value(true, &revert_data[..len])
+}
 
-    // Proposal Management Selectors (Placeholder values)
-    const SUBMIT_PROPOSAL_SELECTOR: u32 = 0x00000010;
-    const GET_PROPOSAL_SELECTOR: u32 = 0x00000011;
-    const UPDATE_PROPOSAL_STATUS_SELECTOR: u32 = 0x00000012;
+/// Decodes the arguments for `selector`, runs it on behalf of `caller`
+/// and ABI-encodes the result into `output`.
+/// Returns the number of bytes written.
+fn dispatch(host: &mut impl Host, caller: Address, selector: u32, args: &[u8], output: &mut [u8]) -> Result<usize, ContractError> {
+    let mut input = Decoder::new(args);
 
-    // Agreement Management Selectors (Placeholder values)
-    const CREATE_AGREEMENT_SELECTOR: u32 = 0x00000020; // From proposal
-    const GET_AGREEMENT_SELECTOR: u32 = 0x00000021;
-    const UPDATE_AGREEMENT_STATUS_SELECTOR: u32 = 0x00000022;
+    // Only the payable selectors may receive value; anything else would be stuck in the contract
+    let value = escrow::value_transferred(host)?;
+    if value != 0 && !matches!(selector, CREATE_JOB_SELECTOR | FUND_JOB_SELECTOR | ACCEPT_PROPOSAL_SELECTOR) {
+        return Err(ContractError::InvalidInput);
+    }
 
     match selector {
         // --- Job Management ---
         CREATE_JOB_SELECTOR => {
-            match job_management::decode_create_job_args(args) {
-                Ok((client_id, budget)) => {
-                    let result = job_management::create_job(client_id, budget);
-                    job_management::encode_job_id_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let params = JobParams {
+                budget: input.read::<u128>()?,
+                content_cid: input.read::<[u8; 32]>()?,
+                category: input.read::<u32>()?,
+                deadline: input.read::<u64>()?,
+                bidding_deadline: input.read::<u64>()?,
+                payment_token: input.read::<Address>()?,
+                min_budget: input.read::<u128>()?,
+            };
+            let job_id = job_management::create_job(host, caller, &params, value)?;
+            Encoder::new(output, 1).push(job_id).finish()
         }
         GET_JOB_SELECTOR => {
-            match job_management::decode_get_job_args(args) {
-                Ok(job_id) => {
-                    let result = job_management::get_job(job_id);
-                    job_management::encode_get_job_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let job_id = input.read::<u32>()?;
+            let (client_id, budget, status_u8, content_cid, category, deadline, bidding_deadline, payment_token, min_budget) =
+                job_management::get_job(host, job_id)?;
+            Encoder::new(output, 9)
+                .push(client_id)
+                .push(budget)
+                .push(status_u8)
+                .push(content_cid)
+                .push(category)
+                .push(deadline)
+                .push(bidding_deadline)
+                .push(payment_token)
+                .push(min_budget)
+                .finish()
         }
         UPDATE_JOB_STATUS_SELECTOR => {
-            match job_management::decode_update_job_status_args(args) {
-                Ok((job_id, new_status)) => {
-                    let result = job_management::update_job_status(job_id, new_status);
-                    job_management::encode_simple_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let job_id = input.read::<u32>()?;
+            let new_status = input.read::<u8>()?;
+            job_management::update_job_status(host, caller, job_id, new_status)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        FUND_JOB_SELECTOR => {
+            let job_id = input.read::<u32>()?;
+            let balance = job_management::fund_job(host, caller, job_id, value)?;
+            Encoder::new(output, 1).push(balance).finish()
+        }
+        FUND_JOB_WITH_TOKEN_SELECTOR => {
+            let job_id = input.read::<u32>()?;
+            let amount = input.read::<u128>()?;
+            let balance = job_management::fund_job_with_token(host, caller, job_id, amount)?;
+            Encoder::new(output, 1).push(balance).finish()
+        }
+        GET_ESCROW_BALANCE_SELECTOR => {
+            let job_id = input.read::<u32>()?;
+            let balance = job_management::get_escrow_balance(host, job_id)?;
+            Encoder::new(output, 1).push(balance).finish()
+        }
+        LIST_JOBS_SELECTOR => {
+            let offset = input.read::<u32>()?;
+            let limit = input.read::<u32>()?;
+            let status_filter = input.read::<u8>()?;
+            let mut page = [0; pagination::MAX_PAGE_SIZE];
+            let (total, count) = job_management::list_jobs(host, offset, limit, status_filter, &mut page)?;
+            Encoder::new(output, 2).push(total).push_array(&page[..count]).finish()
+        }
+        EXPIRE_JOB_SELECTOR => {
+            let job_id = input.read::<u32>()?;
+            job_management::expire_job(host, job_id)?;
+            Encoder::new(output, 1).push(true).finish()
         }
 
         // --- Proposal Management ---
         SUBMIT_PROPOSAL_SELECTOR => {
-            match proposal_management::decode_submit_proposal_args(args) {
-                Ok((job_id, freelancer_id, bid_amount)) => {
-                    let result = proposal_management::submit_proposal(job_id, freelancer_id, bid_amount);
-                    proposal_management::encode_proposal_id_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let job_id = input.read::<u32>()?;
+            let bid_amount = input.read::<u128>()?;
+            let expires_at = input.read::<u64>()?;
+            let cover_hash = input.read::<[u8; 32]>()?;
+            let proposal_id = proposal_management::submit_proposal(host, job_id, caller, bid_amount, expires_at, cover_hash)?;
+            Encoder::new(output, 1).push(proposal_id).finish()
         }
         GET_PROPOSAL_SELECTOR => {
-            match proposal_management::decode_get_proposal_args(args) {
-                Ok(proposal_id) => {
-                    let result = proposal_management::get_proposal(proposal_id);
-                    proposal_management::encode_get_proposal_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let proposal_id = input.read::<u32>()?;
+            let (job_id, freelancer_id, bid_amount, status_u8, expires_at, cover_hash, over_budget) =
+                proposal_management::get_proposal(host, proposal_id)?;
+            Encoder::new(output, 7)
+                .push(job_id)
+                .push(freelancer_id)
+                .push(bid_amount)
+                .push(status_u8)
+                .push(expires_at)
+                .push(cover_hash)
+                .push(over_budget)
+                .finish()
         }
         UPDATE_PROPOSAL_STATUS_SELECTOR => {
-            match proposal_management::decode_update_proposal_status_args(args) {
-                Ok((proposal_id, new_status)) => {
-                    let result = proposal_management::update_proposal_status(proposal_id, new_status);
-                    // If proposal is accepted, an agreement should be created.
-                    // This might be a separate call or an internal trigger.
-                    // For now, let's assume a separate call to create_agreement_from_proposal.
-                    job_management::encode_simple_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let proposal_id = input.read::<u32>()?;
+            let new_status = input.read::<u8>()?;
+            // Accepting here still needs a createAgreementFromProposal call to start the job;
+            // clients can do both at once with acceptProposal.
+            proposal_management::update_proposal_status(host, caller, proposal_id, new_status)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        UPDATE_PROPOSAL_SELECTOR => {
+            let proposal_id = input.read::<u32>()?;
+            let new_bid = input.read::<u128>()?;
+            let new_cover_hash = input.read::<[u8; 32]>()?;
+            proposal_management::update_proposal(host, caller, proposal_id, new_bid, new_cover_hash)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        WITHDRAW_PROPOSAL_SELECTOR => {
+            let proposal_id = input.read::<u32>()?;
+            proposal_management::withdraw_proposal(host, caller, proposal_id)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        COUNTER_OFFER_SELECTOR => {
+            let proposal_id = input.read::<u32>()?;
+            let amount = input.read::<u128>()?;
+            let terms_hash = input.read::<[u8; 32]>()?;
+            let round = proposal_management::counter_offer(host, caller, proposal_id, amount, terms_hash)?;
+            Encoder::new(output, 1).push(round).finish()
+        }
+        GET_NEGOTIATION_SELECTOR => {
+            let proposal_id = input.read::<u32>()?;
+            let (round_count, current_amount) = proposal_management::get_negotiation(host, proposal_id)?;
+            Encoder::new(output, 2).push(round_count).push(current_amount).finish()
+        }
+        GET_COUNTER_OFFER_SELECTOR => {
+            let proposal_id = input.read::<u32>()?;
+            let round = input.read::<u32>()?;
+            let (from, amount, terms_hash) = proposal_management::get_counter_offer(host, proposal_id, round)?;
+            Encoder::new(output, 3).push(from).push(amount).push(terms_hash).finish()
+        }
+        GET_PROPOSAL_BY_FREELANCER_SELECTOR => {
+            let job_id = input.read::<u32>()?;
+            let freelancer = input.read::<Address>()?;
+            let (exists, proposal_id) = proposal_management::get_proposal_by_freelancer(host, job_id, &freelancer)?;
+            Encoder::new(output, 2).push(exists).push(proposal_id).finish()
+        }
+        LIST_PROPOSALS_FOR_JOB_SELECTOR => {
+            let job_id = input.read::<u32>()?;
+            let offset = input.read::<u32>()?;
+            let limit = input.read::<u32>()?;
+            let mut page = [0; pagination::MAX_PAGE_SIZE];
+            let (total, count) = proposal_management::list_proposals_for_job(host, job_id, offset, limit, &mut page)?;
+            Encoder::new(output, 2).push(total).push_array(&page[..count]).finish()
         }
 
         // --- Agreement Management ---
         CREATE_AGREEMENT_SELECTOR => { // create_agreement_from_proposal
-            match agreement_management::decode_create_agreement_args(args) {
-                Ok(proposal_id) => {
-                    let result = agreement_management::create_agreement_from_proposal(proposal_id);
-                    agreement_management::encode_agreement_id_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let proposal_id = input.read::<u32>()?;
+            let mut schedule = [(0, [0u8; 32]); agreement_management::MAX_MILESTONES];
+            let count = read_schedule(&mut input, &mut schedule)?;
+            let agreement_id =
+                agreement_management::create_agreement_from_proposal(host, caller, proposal_id, &schedule[..count])?;
+            Encoder::new(output, 1).push(agreement_id).finish()
+        }
+        ACCEPT_PROPOSAL_SELECTOR => {
+            let proposal_id = input.read::<u32>()?;
+            let mut schedule = [(0, [0u8; 32]); agreement_management::MAX_MILESTONES];
+            let count = read_schedule(&mut input, &mut schedule)?;
+            let agreement_id =
+                agreement_management::accept_proposal(host, caller, proposal_id, &schedule[..count], value)?;
+            Encoder::new(output, 1).push(agreement_id).finish()
         }
         GET_AGREEMENT_SELECTOR => {
-            match agreement_management::decode_get_agreement_args(args) {
-                Ok(agreement_id) => {
-                    let result = agreement_management::get_agreement(agreement_id);
-                    agreement_management::encode_get_agreement_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let agreement_id = input.read::<u32>()?;
+            let (job_id, client_id, freelancer_id, total_amount, status_u8, milestone_count) =
+                agreement_management::get_agreement(host, agreement_id)?;
+            Encoder::new(output, 6)
+                .push(job_id)
+                .push(client_id)
+                .push(freelancer_id)
+                .push(total_amount)
+                .push(status_u8)
+                .push(milestone_count)
+                .finish()
         }
         UPDATE_AGREEMENT_STATUS_SELECTOR => {
-            match agreement_management::decode_update_agreement_status_args(args) {
-                Ok((agreement_id, new_status)) => {
-                    let result = agreement_management::update_agreement_status(agreement_id, new_status);
-                    job_management::encode_simple_result(result, &mut output_buffer);
-                }
-                Err(e) => output_buffer[31] = e as u8,
-            }
+            let agreement_id = input.read::<u32>()?;
+            let new_status = input.read::<u8>()?;
+            agreement_management::update_agreement_status(host, caller, agreement_id, new_status)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        LIST_AGREEMENTS_FOR_USER_SELECTOR => {
+            let user = input.read::<Address>()?;
+            let offset = input.read::<u32>()?;
+            let limit = input.read::<u32>()?;
+            let mut page = [0; pagination::MAX_PAGE_SIZE];
+            let (total, count) = agreement_management::list_agreements_for_user(host, &user, offset, limit, &mut page);
+            Encoder::new(output, 2).push(total).push_array(&page[..count]).finish()
+        }
+        GET_MILESTONE_SELECTOR => {
+            let agreement_id = input.read::<u32>()?;
+            let index = input.read::<u32>()?;
+            let (amount, description_hash, status_u8) = agreement_management::get_milestone(host, agreement_id, index)?;
+            Encoder::new(output, 3).push(amount).push(description_hash).push(status_u8).finish()
+        }
+        SUBMIT_MILESTONE_SELECTOR => {
+            let agreement_id = input.read::<u32>()?;
+            let index = input.read::<u32>()?;
+            agreement_management::submit_milestone(host, caller, agreement_id, index)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        APPROVE_MILESTONE_SELECTOR => {
+            let agreement_id = input.read::<u32>()?;
+            let index = input.read::<u32>()?;
+            agreement_management::approve_milestone(host, caller, agreement_id, index)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        RELEASE_MILESTONE_SELECTOR => {
+            let agreement_id = input.read::<u32>()?;
+            let index = input.read::<u32>()?;
+            agreement_management::release_milestone(host, caller, agreement_id, index)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        RESOLVE_DISPUTE_SELECTOR => {
+            let agreement_id = input.read::<u32>()?;
+            let client_share_bps = input.read::<u16>()?;
+            agreement_management::resolve_dispute(host, caller, agreement_id, client_share_bps)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        GET_PAYOUT_BREAKDOWN_SELECTOR => {
+            let agreement_id = input.read::<u32>()?;
+            let (total_amount, fee_bps, platform_fee, freelancer_amount, released_amount) =
+                agreement_management::get_payout_breakdown(host, agreement_id)?;
+            Encoder::new(output, 5)
+                .push(total_amount)
+                .push(fee_bps)
+                .push(platform_fee)
+                .push(freelancer_amount)
+                .push(released_amount)
+                .finish()
+        }
+
+        // --- Reputation ---
+        RATE_COUNTERPARTY_SELECTOR => {
+            let agreement_id = input.read::<u32>()?;
+            let score = input.read::<u8>()?;
+            let review_hash = input.read::<[u8; 32]>()?;
+            reputation::rate_counterparty(host, caller, agreement_id, score, review_hash)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        GET_REPUTATION_SELECTOR => {
+            let account = input.read::<Address>()?;
+            let (rating_count, rating_sum, completed_jobs, disputes) = reputation::get_reputation(host, &account);
+            Encoder::new(output, 4)
+                .push(rating_count)
+                .push(rating_sum)
+                .push(completed_jobs)
+                .push(disputes)
+                .finish()
+        }
+        GET_REVIEW_SELECTOR => {
+            let agreement_id = input.read::<u32>()?;
+            let reviewer = input.read::<Address>()?;
+            let (score, review_hash) = reputation::get_review(host, agreement_id, &reviewer)?;
+            Encoder::new(output, 2).push(score).push(review_hash).finish()
+        }
+
+        // --- Admin ---
+        GET_CONFIG_SELECTOR => {
+            let (owner, pending_owner, arbitrator, fee_bps, min_job_budget, max_milestones) = admin::get_config(host);
+            Encoder::new(output, 6)
+                .push(owner)
+                .push(pending_owner)
+                .push(arbitrator)
+                .push(fee_bps)
+                .push(min_job_budget)
+                .push(max_milestones)
+                .finish()
+        }
+        SET_CONFIG_SELECTOR => {
+            let config = Config {
+                arbitrator: input.read::<Address>()?,
+                fee_bps: input.read::<u16>()?,
+                min_job_budget: input.read::<u128>()?,
+                max_milestones: input.read::<u32>()?,
+            };
+            admin::set_config(host, caller, config)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        TRANSFER_OWNERSHIP_SELECTOR => {
+            let new_owner = input.read::<Address>()?;
+            admin::transfer_ownership(host, caller, new_owner)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        ACCEPT_OWNERSHIP_SELECTOR => {
+            admin::accept_ownership(host, caller)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        PAUSE_SELECTOR => {
+            admin::set_paused(host, caller, true)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        UNPAUSE_SELECTOR => {
+            admin::set_paused(host, caller, false)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        PAUSED_SELECTOR => Encoder::new(output, 1).push(storage::is_paused(host)).finish(),
+        WITHDRAW_FEES_SELECTOR => {
+            let to = input.read::<Address>()?;
+            let amount = input.read::<u128>()?;
+            admin::withdraw_fees(host, caller, escrow::NATIVE_TOKEN, to, amount)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        GET_TREASURY_BALANCE_SELECTOR => {
+            Encoder::new(output, 1).push(storage::treasury_balance(host, &escrow::NATIVE_TOKEN)).finish()
+        }
+        WITHDRAW_TOKEN_FEES_SELECTOR => {
+            let token = input.read::<Address>()?;
+            let to = input.read::<Address>()?;
+            let amount = input.read::<u128>()?;
+            admin::withdraw_fees(host, caller, token, to, amount)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        GET_TOKEN_TREASURY_BALANCE_SELECTOR => {
+            let token = input.read::<Address>()?;
+            Encoder::new(output, 1).push(storage::treasury_balance(host, &token)).finish()
         }
 
-        _ => {
-            // Unknown selector
-            api::return_value(ReturnFlags::REVERT, &[]);
-            return;
+        // --- Upgrades ---
+        UPGRADE_SELECTOR => {
+            let code_hash = input.read::<[u8; 32]>()?;
+            upgrade::upgrade(host, caller, code_hash)?;
+            Encoder::new(output, 1).push(true).finish()
+        }
+        MIGRATE_SELECTOR => {
+            let max_records = input.read::<u32>()?;
+            let (migrated, done) = upgrade::migrate(host, caller, max_records)?;
+            Encoder::new(output, 2).push(migrated).push(done).finish()
         }
+        GET_STORAGE_VERSION_SELECTOR => {
+            let (stored_version, code_version) = upgrade::get_storage_version(host);
+            Encoder::new(output, 2).push(stored_version).push(code_version).finish()
+        }
+
+        // Unknown selector
+        _ => Err(ContractError::UnknownSelector(selector)),
+    }
+}
+
+/// Decodes a milestone schedule passed as (uint128[] amounts, bytes32[] descriptionHashes),
+/// where amounts[i] and descriptionHashes[i] describe milestone i.
+/// Returns the number of milestones written to `schedule`.
+fn read_schedule(
+    input: &mut Decoder,
+    schedule: &mut [(Balance, [u8; 32]); agreement_management::MAX_MILESTONES],
+) -> Result<usize, ContractError> {
+    let (count, mut amounts) = input.read_array()?;
+    let (hash_count, mut hashes) = input.read_array()?;
+    if count != hash_count || count > agreement_management::MAX_MILESTONES {
+        return Err(ContractError::InvalidInput);
+    }
+    for milestone in schedule[..count].iter_mut() {
+        *milestone = (amounts.read::<u128>()?, hashes.read::<[u8; 32]>()?);
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::Word;
+    use crate::mock::{call_data, word, MockHost};
+
+    const CLIENT: Address = [1u8; 20];
+    const OWNER: Address = [9u8; 20];
+
+    fn deployed() -> MockHost {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        host
+    }
+
+    fn create_job_call(budget: u128) -> Vec<u8> {
+        call_data(
+            CREATE_JOB_SELECTOR,
+            &[budget.to_word(), [0xcd; 32], 7u32.to_word(), 1_700_000_000u64.to_word(), 0u64.to_word(), [0u8; 32], 0u128.to_word()],
+        )
+    }
+
+    #[test]
+    fn constructor_sets_owner() {
+        assert_eq!(storage::owner(&deployed()), OWNER);
+    }
+
+    #[test]
+    fn config_round_trips_through_abi() {
+        const ARBITRATOR: Address = [3u8; 20];
+        let mut host = deployed();
+        let args = [ARBITRATOR.to_word(), 150u16.to_word(), 10u128.to_word(), 8u32.to_word()];
+        let (reverted, data) = host.execute(CLIENT, 0, &call_data(SET_CONFIG_SELECTOR, &args));
+        assert!(reverted);
+        assert_eq!(data[..4], keccak::selector("Unauthorized(address)").to_be_bytes());
+        assert!(!host.execute(OWNER, 0, &call_data(SET_CONFIG_SELECTOR, &args)).0);
+        assert!(!host.execute(OWNER, 0, &call_data(TRANSFER_OWNERSHIP_SELECTOR, &[CLIENT.to_word()])).0);
+
+        let (reverted, data) = host.execute(CLIENT, 0, &call_data(GET_CONFIG_SELECTOR, &[]));
+        assert!(!reverted);
+        assert_eq!(data.len(), 6 * abi::WORD);
+        assert_eq!(word::<Address>(&data, 0), OWNER);
+        assert_eq!(word::<Address>(&data, 1), CLIENT);
+        assert_eq!(word::<Address>(&data, 2), ARBITRATOR);
+        assert_eq!(word::<u16>(&data, 3), 150);
+        assert_eq!(word::<u128>(&data, 4), 10);
+        assert_eq!(word::<u32>(&data, 5), 8);
+
+        assert!(!host.execute(CLIENT, 0, &call_data(ACCEPT_OWNERSHIP_SELECTOR, &[])).0);
+        assert_eq!(storage::owner(&host), CLIENT);
+    }
+
+    #[test]
+    fn paused_contract_rejects_mutations_but_serves_views() {
+        let mut host = deployed();
+        host.execute(CLIENT, 500, &create_job_call(1_000));
+        assert!(!host.execute(OWNER, 0, &call_data(PAUSE_SELECTOR, &[])).0);
+        assert!(word::<bool>(&host.execute(CLIENT, 0, &call_data(PAUSED_SELECTOR, &[])).1, 0));
+
+        let (reverted, data) = host.execute(CLIENT, 500, &create_job_call(1_000));
+        assert!(reverted);
+        assert_eq!(data, keccak::selector("EnforcedPause()").to_be_bytes());
+        assert!(!host.execute(CLIENT, 0, &call_data(GET_JOB_SELECTOR, &[0u32.to_word()])).0);
+
+        // The client can still cancel and get the escrow back
+        let (reverted, _) =
+            host.execute(CLIENT, 0, &call_data(UPDATE_JOB_STATUS_SELECTOR, &[0u32.to_word(), 3u8.to_word()]));
+        assert!(!reverted);
+        assert_eq!(host.transferred_to(&CLIENT), 500);
+
+        assert!(!host.execute(OWNER, 0, &call_data(UNPAUSE_SELECTOR, &[])).0);
+        assert!(!host.execute(CLIENT, 0, &create_job_call(1_000)).0);
+    }
+
+    #[test]
+    fn upgrade_and_migrate_through_abi() {
+        let mut host = deployed();
+        let (reverted, _) = host.execute(CLIENT, 0, &call_data(UPGRADE_SELECTOR, &[[0xab; 32]]));
+        assert!(reverted);
+        assert_eq!(host.code_hash, None);
+        assert!(!host.execute(OWNER, 0, &call_data(UPGRADE_SELECTOR, &[[0xab; 32]])).0);
+        assert_eq!(host.code_hash, Some([0xab; 32]));
+
+        let (_, data) = host.execute(OWNER, 0, &call_data(MIGRATE_SELECTOR, &[10u32.to_word()]));
+        assert_eq!(word::<u32>(&data, 0), 0);
+        assert!(word::<bool>(&data, 1));
+        let (_, data) = host.execute(CLIENT, 0, &call_data(GET_STORAGE_VERSION_SELECTOR, &[]));
+        assert_eq!(word::<u8>(&data, 0), storage::STORAGE_VERSION);
+        assert_eq!(word::<u8>(&data, 1), storage::STORAGE_VERSION);
+    }
+
+    #[test]
+    fn create_and_get_job_through_abi() {
+        let mut host = deployed();
+        let (reverted, data) = host.execute(CLIENT, 500, &create_job_call(1_000));
+        assert!(!reverted);
+        assert_eq!(word::<u32>(&data, 0), 0);
+
+        let (reverted, data) = host.execute(CLIENT, 0, &call_data(GET_JOB_SELECTOR, &[0u32.to_word()]));
+        assert!(!reverted);
+        assert_eq!(data.len(), 9 * abi::WORD);
+        assert_eq!(word::<Address>(&data, 0), CLIENT);
+        assert_eq!(word::<u128>(&data, 1), 1_000);
+        assert_eq!(word::<u8>(&data, 2), 0);
+        assert_eq!(word::<[u8; 32]>(&data, 3), [0xcd; 32]);
+        assert_eq!(word::<u32>(&data, 4), 7);
+        assert_eq!(word::<u64>(&data, 5), 1_700_000_000);
+        assert_eq!(word::<Address>(&data, 7), escrow::NATIVE_TOKEN);
+
+        let (_, data) = host.execute(CLIENT, 0, &call_data(GET_ESCROW_BALANCE_SELECTOR, &[0u32.to_word()]));
+        assert_eq!(word::<u128>(&data, 0), 500);
+    }
+
+    #[test]
+    fn token_job_is_funded_through_abi() {
+        const TOKEN: Address = [0x70; 20];
+        let mut host = deployed();
+        let mut token = mock::MockToken::default();
+        token.balances.insert(CLIENT, 1_000);
+        token.allowances.insert((CLIENT, mock::CONTRACT), 1_000);
+        host.tokens.insert(TOKEN, token);
+
+        let args =
+            [1_000u128.to_word(), [0xcd; 32], 7u32.to_word(), 0u64.to_word(), 0u64.to_word(), TOKEN.to_word(), 0u128.to_word()];
+        assert!(!host.execute(CLIENT, 0, &call_data(CREATE_JOB_SELECTOR, &args)).0);
+        let (reverted, data) =
+            host.execute(CLIENT, 0, &call_data(FUND_JOB_WITH_TOKEN_SELECTOR, &[0u32.to_word(), 400u128.to_word()]));
+        assert!(!reverted);
+        assert_eq!(word::<u128>(&data, 0), 400);
+        assert_eq!(host.token_balance(&TOKEN, &mock::CONTRACT), 400);
+
+        // A failed pull reverts the whole call, escrow credit included
+        let (reverted, _) =
+            host.execute(CLIENT, 0, &call_data(FUND_JOB_WITH_TOKEN_SELECTOR, &[0u32.to_word(), 700u128.to_word()]));
+        assert!(reverted);
+        let (_, data) = host.execute(CLIENT, 0, &call_data(GET_ESCROW_BALANCE_SELECTOR, &[0u32.to_word()]));
+        assert_eq!(word::<u128>(&data, 0), 400);
+    }
+
+    #[test]
+    fn unknown_selector_reverts_with_custom_error() {
+        let mut host = deployed();
+        let (reverted, data) = host.execute(CLIENT, 0, &call_data(0xdeadbeef, &[]));
+        assert!(reverted);
+        assert_eq!(data[..4], keccak::selector("UnknownSelector(bytes4)").to_be_bytes());
+        assert_eq!(word::<[u8; 4]>(&data[4..], 0), [0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn short_or_malformed_call_data_reverts() {
+        let mut host = deployed();
+        assert_eq!(host.execute(CLIENT, 0, &[0x01, 0x02]), (true, vec![]));
+
+        // u8 argument with dirty high bytes
+        let (reverted, data) =
+            host.execute(CLIENT, 0, &call_data(UPDATE_JOB_STATUS_SELECTOR, &[0u32.to_word(), 256u16.to_word()]));
+        assert!(reverted);
+        assert_eq!(data, keccak::selector("InvalidInput()").to_be_bytes());
+    }
+
+    #[test]
+    fn value_sent_to_non_payable_selector_is_rejected() {
+        let mut host = deployed();
+        let (reverted, data) = host.execute(CLIENT, 1, &call_data(GET_JOB_SELECTOR, &[0u32.to_word()]));
+        assert!(reverted);
+        assert_eq!(data, keccak::selector("InvalidInput()").to_be_bytes());
     }
 
-    // Check if the last byte of output_buffer (error indicator) is non-zero
-    if output_buffer[31] != 0 {
-         // If it's a known error from ContractError enum
-        if output_buffer[31] >= ContractError::InvalidOperation as u8 && output_buffer[31] <= ContractError::Unauthorized as u8 {
-             api::return_value(ReturnFlags::REVERT, &[output_buffer[31]]); // Return the error code
-        } else {
-            api::return_value(ReturnFlags::REVERT, &[]); // Generic revert for unknown errors
+    #[test]
+    fn reverted_call_leaves_no_trace() {
+        let mut host = deployed();
+        host.execute(CLIENT, 500, &create_job_call(1_000));
+        host.failing_callees.push(CLIENT);
+        let storage = host.storage.clone();
+        let events = host.events.len();
+
+        // Cancelling refunds the client, whose transfer fails, so the status change is rolled back too
+        let (reverted, _) =
+            host.execute(CLIENT, 0, &call_data(UPDATE_JOB_STATUS_SELECTOR, &[0u32.to_word(), 3u8.to_word()]));
+        assert!(reverted);
+        assert_eq!(host.storage, storage);
+        assert_eq!(host.events.len(), events);
+    }
+
+    #[test]
+    fn create_agreement_decodes_milestone_schedule() {
+        const FREELANCER: Address = [2u8; 20];
+        let mut host = deployed();
+        host.execute(CLIENT, 1_000, &create_job_call(1_000));
+        host.execute(FREELANCER, 0, &call_data(SUBMIT_PROPOSAL_SELECTOR, &[0u32.to_word(), 800u128.to_word(), 0u64.to_word(), [0u8; 32]]));
+        host.execute(CLIENT, 0, &call_data(UPDATE_PROPOSAL_STATUS_SELECTOR, &[0u32.to_word(), 1u8.to_word()]));
+
+        // (proposalId, uint128[] amounts, bytes32[] hashes): the arrays start at words 3 and 6
+        let args = [
+            0u32.to_word(),
+            96u32.to_word(),
+            192u32.to_word(),
+            2u32.to_word(),
+            300u128.to_word(),
+            500u128.to_word(),
+            2u32.to_word(),
+            [0xaa; 32],
+            [0xbb; 32],
+        ];
+        let (reverted, data) = host.execute(CLIENT, 0, &call_data(CREATE_AGREEMENT_SELECTOR, &args));
+        assert!(!reverted);
+        assert_eq!(word::<u32>(&data, 0), 0);
+
+        let (_, data) = host.execute(CLIENT, 0, &call_data(GET_MILESTONE_SELECTOR, &[0u32.to_word(), 1u32.to_word()]));
+        assert_eq!(word::<u128>(&data, 0), 500);
+        assert_eq!(word::<[u8; 32]>(&data, 1), [0xbb; 32]);
+        assert_eq!(word::<u8>(&data, 2), 0);
+
+        // Mismatched array lengths
+        let mut args = args;
+        args[6] = 1u32.to_word();
+        let (reverted, data) = host.execute(CLIENT, 0, &call_data(CREATE_AGREEMENT_SELECTOR, &args));
+        assert!(reverted);
+        assert_eq!(data, keccak::selector("InvalidInput()").to_be_bytes());
+    }
+
+    #[test]
+    fn accept_proposal_is_all_or_nothing() {
+        const FREELANCER: Address = [2u8; 20];
+        const RIVAL: Address = [3u8; 20];
+        let mut host = deployed();
+        host.execute(CLIENT, 500, &create_job_call(1_000));
+        host.execute(FREELANCER, 0, &call_data(SUBMIT_PROPOSAL_SELECTOR, &[0u32.to_word(), 800u128.to_word(), 0u64.to_word(), [0u8; 32]]));
+        host.execute(RIVAL, 0, &call_data(SUBMIT_PROPOSAL_SELECTOR, &[0u32.to_word(), 700u128.to_word(), 0u64.to_word(), [0u8; 32]]));
+        // (proposalId, empty amounts, empty hashes): one milestone for the whole amount
+        let args = [0u32.to_word(), 96u32.to_word(), 128u32.to_word(), 0u32.to_word(), 0u32.to_word()];
+
+        // Not enough escrow: nothing is accepted or rejected
+        let (reverted, data) = host.execute(CLIENT, 200, &call_data(ACCEPT_PROPOSAL_SELECTOR, &args));
+        assert!(reverted);
+        assert_eq!(data[..4], keccak::selector("InsufficientEscrow(uint128,uint128)").to_be_bytes());
+        for proposal_id in [0u32, 1] {
+            let (_, data) = host.execute(CLIENT, 0, &call_data(GET_PROPOSAL_SELECTOR, &[proposal_id.to_word()]));
+            assert_eq!(word::<u8>(&data, 3), 0);
         }
-    } else {
-        api::return_value(ReturnFlags::empty(), &output_buffer);
+
+        let (reverted, data) = host.execute(CLIENT, 300, &call_data(ACCEPT_PROPOSAL_SELECTOR, &args));
+        assert!(!reverted);
+        assert_eq!(word::<u32>(&data, 0), 0);
+        let (_, data) = host.execute(CLIENT, 0, &call_data(GET_PROPOSAL_SELECTOR, &[1u32.to_word()]));
+        assert_eq!(word::<u8>(&data, 3), 2);
+        let (_, data) = host.execute(CLIENT, 0, &call_data(GET_JOB_SELECTOR, &[0u32.to_word()]));
+        assert_eq!(word::<u8>(&data, 2), 1);
+    }
+
+    #[test]
+    fn list_jobs_returns_total_and_id_array() {
+        let mut host = deployed();
+        for _ in 0..3 {
+            host.execute(CLIENT, 0, &create_job_call(1_000));
+        }
+        let args = [1u32.to_word(), 10u32.to_word(), job_management::ANY_STATUS.to_word()];
+        let (reverted, data) = host.execute(CLIENT, 0, &call_data(LIST_JOBS_SELECTOR, &args));
+        assert!(!reverted);
+
+        // (uint32 total, uint32[] ids): head, then the array's length and elements
+        assert_eq!(word::<u32>(&data, 0), 3);
+        assert_eq!(word::<u32>(&data, 1), 64);
+        assert_eq!(word::<u32>(&data, 2), 2);
+        assert_eq!((word::<u32>(&data, 3), word::<u32>(&data, 4)), (1, 2));
+        assert_eq!(data.len(), 5 * abi::WORD);
     }
 }
@@ -0,0 +1,237 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/mock.rs
+
+// In-memory host for unit tests (`std` feature only).
+// Storage, caller, value, events, outgoing transfers and return data are plain fields,
+// so tests can set up a call and inspect everything it did afterwards.
+
+use std::collections::BTreeMap;
+
+use crate::abi::{Decoder, Word, WORD};
+use crate::host::Host;
+use crate::keccak::selector;
+use crate::types::{Address, Balance, JobParams};
+
+/// Builds call data for `selector` with static arguments.
+pub fn call_data(selector: u32, args: &[[u8; WORD]]) -> Vec<u8> {
+    let mut data = selector.to_be_bytes().to_vec();
+    for arg in args {
+        data.extend_from_slice(arg);
+    }
+    data
+}
+
+/// Decodes the `index`-th word of ABI-encoded return data.
+pub fn word<T: Word>(data: &[u8], index: usize) -> T {
+    let word: &[u8; WORD] = data[index * WORD..(index + 1) * WORD].try_into().unwrap();
+    T::from_word(word).expect("invalid word")
+}
+
+/// createJob parameters with only a budget set.
+pub fn job_params(budget: Balance) -> JobParams {
+    JobParams { budget, ..JobParams::default() }
+}
+
+/// Address the contract under test is deployed at.
+pub const CONTRACT: Address = [0xcc; 20];
+
+/// An ERC-20 token contract living at some address on the mock chain.
+/// Understands `transfer` and `transferFrom`; failed calls revert like OpenZeppelin's.
+#[derive(Default, Clone)]
+pub struct MockToken {
+    pub balances: BTreeMap<Address, Balance>,
+    /// (owner, spender) -> amount
+    pub allowances: BTreeMap<(Address, Address), Balance>,
+    /// Return `false` instead of moving tokens, like some non-reverting tokens do.
+    pub returns_false: bool,
+}
+
+impl MockToken {
+    /// Runs a call from `sender`. Returns the ABI-encoded result, or None if it reverted.
+    fn call(&mut self, sender: Address, input: &[u8]) -> Option<[u8; WORD]> {
+        let function = u32::from_be_bytes(input.get(..4)?.try_into().ok()?);
+        let mut args = Decoder::new(&input[4..]);
+        if self.returns_false {
+            return Some(false.to_word());
+        }
+        if function == selector("transfer(address,uint256)") {
+            let to = args.read::<Address>().ok()?;
+            let amount = args.read::<u128>().ok()?;
+            self.move_balance(sender, to, amount)?;
+        } else if function == selector("transferFrom(address,address,uint256)") {
+            let from = args.read::<Address>().ok()?;
+            let to = args.read::<Address>().ok()?;
+            let amount = args.read::<u128>().ok()?;
+            let allowance = self.allowances.get(&(from, sender)).copied().unwrap_or_default().checked_sub(amount)?;
+            self.move_balance(from, to, amount)?;
+            self.allowances.insert((from, sender), allowance);
+        } else {
+            return None;
+        }
+        Some(true.to_word())
+    }
+
+    fn move_balance(&mut self, from: Address, to: Address, amount: Balance) -> Option<()> {
+        let from_balance = self.balances.entry(from).or_default();
+        *from_balance = from_balance.checked_sub(amount)?;
+        *self.balances.entry(to).or_default() += amount;
+        Some(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub topics: Vec<[u8; 32]>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Default, Clone)]
+pub struct MockHost {
+    /// This contract's own address, CONTRACT unless a test changes it.
+    pub address: Address,
+    pub caller: Address,
+    pub value: Balance,
+    /// Block timestamp in unix seconds, returned by `now()`.
+    pub timestamp: u64,
+    pub call_data: Vec<u8>,
+    pub storage: BTreeMap<[u8; 32], Vec<u8>>,
+    pub events: Vec<Event>,
+    /// Native value sent out by the contract: (recipient, amount).
+    pub transfers: Vec<(Address, Balance)>,
+    /// ERC-20 contracts by address. Calls to them run the token instead of moving native value.
+    pub tokens: BTreeMap<Address, MockToken>,
+    /// Outgoing calls to these addresses fail, e.g. to simulate a rejecting recipient.
+    pub failing_callees: Vec<Address>,
+    /// Set by `set_code_hash`.
+    pub code_hash: Option<[u8; 32]>,
+    /// Set by `return_value`: (reverted, data).
+    pub returned: Option<(bool, Vec<u8>)>,
+}
+
+impl MockHost {
+    pub fn new() -> Self {
+        Self { address: CONTRACT, ..Self::default() }
+    }
+
+    /// Runs the constructor with `owner` as the deployer and no constructor input.
+    pub fn deploy(&mut self, owner: Address) {
+        let (reverted, _) = self.deploy_with(owner, &[]);
+        assert!(!reverted, "constructor reverted");
+    }
+
+    /// Runs the constructor with `deployer` and ABI-encoded constructor `input`.
+    /// Returns (reverted, return data); storage and events are rolled back if it reverted.
+    pub fn deploy_with(&mut self, deployer: Address, input: &[u8]) -> (bool, Vec<u8>) {
+        let snapshot = (self.storage.clone(), self.events.len());
+        self.caller = deployer;
+        self.value = 0;
+        self.call_data = input.to_vec();
+        self.returned = None;
+
+        crate::constructor(self);
+
+        let (reverted, data) = self.returned.take().unwrap_or_default();
+        if reverted {
+            self.storage = snapshot.0;
+            self.events.truncate(snapshot.1);
+        }
+        (reverted, data)
+    }
+
+    /// Runs a full contract call the way the chain would: sets caller, value and call data,
+    /// executes it and rolls storage, events and transfers back if it reverted.
+    /// Returns (reverted, return data).
+    pub fn execute(&mut self, caller: Address, value: Balance, call_data: &[u8]) -> (bool, Vec<u8>) {
+        let snapshot = (self.storage.clone(), self.events.len(), self.transfers.len(), self.code_hash, self.tokens.clone());
+        self.caller = caller;
+        self.value = value;
+        self.call_data = call_data.to_vec();
+        self.returned = None;
+
+        crate::execute(self);
+
+        let (reverted, data) = self.returned.take().expect("contract did not return");
+        if reverted {
+            self.storage = snapshot.0;
+            self.events.truncate(snapshot.1);
+            self.transfers.truncate(snapshot.2);
+            self.code_hash = snapshot.3;
+            self.tokens = snapshot.4;
+        }
+        (reverted, data)
+    }
+
+    /// `owner`'s balance of the mock ERC-20 at `token`.
+    pub fn token_balance(&self, token: &Address, owner: &Address) -> Balance {
+        self.tokens.get(token).and_then(|t| t.balances.get(owner)).copied().unwrap_or_default()
+    }
+
+    /// Total native value the contract has sent to `to`.
+    pub fn transferred_to(&self, to: &Address) -> Balance {
+        self.transfers.iter().filter(|(addr, _)| addr == to).map(|(_, amount)| amount).sum()
+    }
+}
+
+impl Host for MockHost {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn caller(&self) -> Address {
+        self.caller
+    }
+
+    fn value_transferred(&self) -> [u8; 32] {
+        let mut value = [0u8; 32];
+        value[..16].copy_from_slice(&self.value.to_le_bytes());
+        value
+    }
+
+    fn now(&self) -> u64 {
+        self.timestamp
+    }
+
+    fn call_data_size(&self) -> usize {
+        self.call_data.len()
+    }
+
+    fn call_data_copy(&self, output: &mut [u8]) {
+        output.copy_from_slice(&self.call_data[..output.len()]);
+    }
+
+    fn get_storage(&self, key: &[u8; 32], output: &mut [u8]) -> Option<usize> {
+        let value = self.storage.get(key)?;
+        output.get_mut(..value.len())?.copy_from_slice(value);
+        Some(value.len())
+    }
+
+    fn set_storage(&mut self, key: &[u8; 32], value: &[u8]) {
+        self.storage.insert(*key, value.to_vec());
+    }
+
+    fn call(&mut self, callee: &Address, value: &[u8; 32], input: &[u8], output: &mut [u8]) -> Option<usize> {
+        if self.failing_callees.contains(callee) {
+            return None;
+        }
+        let sender = self.address;
+        if let Some(token) = self.tokens.get_mut(callee) {
+            let result = token.call(sender, input)?;
+            output.get_mut(..WORD)?.copy_from_slice(&result);
+            return Some(WORD);
+        }
+        let amount = u128::from_le_bytes(value[..16].try_into().unwrap());
+        self.transfers.push((*callee, amount));
+        Some(0)
+    }
+
+    fn deposit_event(&mut self, topics: &[[u8; 32]], data: &[u8]) {
+        self.events.push(Event { topics: topics.to_vec(), data: data.to_vec() });
+    }
+
+    fn set_code_hash(&mut self, code_hash: &[u8; 32]) {
+        self.code_hash = Some(*code_hash);
+    }
+
+    fn return_value(&mut self, revert: bool, data: &[u8]) {
+        self.returned = Some((revert, data.to_vec()));
+    }
+}
@@ -0,0 +1,50 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/pagination.rs
+
+// Paging for the list views.
+// Records are found by scanning ids from 0 up to the id counter, which is cheap because
+// every collection is capped (MAX_JOBS etc.). There is no allocator, so each page is
+// written into a fixed buffer of MAX_PAGE_SIZE ids.
+
+/// Most ids a single list call returns; larger limits are clamped.
+pub const MAX_PAGE_SIZE: usize = 50;
+
+/// Copies the matching ids at positions offset..offset+limit into `page`.
+/// Returns (total number of matches, number of ids written).
+pub fn paginate(matches: impl Iterator<Item = u32>, offset: u32, limit: u32, page: &mut [u32; MAX_PAGE_SIZE]) -> (u32, usize) {
+    let limit = (limit as usize).min(MAX_PAGE_SIZE);
+    let mut total: u32 = 0;
+    let mut count = 0;
+    for id in matches {
+        if total >= offset && count < limit {
+            page[count] = id;
+            count += 1;
+        }
+        total += 1;
+    }
+    (total, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_through_matches() {
+        let mut page = [0u32; MAX_PAGE_SIZE];
+        let evens = || (0..20).filter(|id| id % 2 == 0);
+
+        assert_eq!(paginate(evens(), 0, 3, &mut page), (10, 3));
+        assert_eq!(page[..3], [0, 2, 4]);
+        assert_eq!(paginate(evens(), 8, 5, &mut page), (10, 2));
+        assert_eq!(page[..2], [16, 18]);
+        assert_eq!(paginate(evens(), 10, 5, &mut page), (10, 0));
+        assert_eq!(paginate(evens(), 0, 0, &mut page), (10, 0));
+    }
+
+    #[test]
+    fn clamps_limit_to_page_size() {
+        let mut page = [0u32; MAX_PAGE_SIZE];
+        assert_eq!(paginate(0..100, 0, u32::MAX, &mut page), (100, MAX_PAGE_SIZE));
+        assert_eq!(page[MAX_PAGE_SIZE - 1], MAX_PAGE_SIZE as u32 - 1);
+    }
+}
@@ -1,173 +1,672 @@
 // filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/proposal_management.rs
-#![cfg_attr(not(feature = "std"), no_std)]
 
-use crate::types::{Proposal, ProposalId, ProposalStatus, JobId, UserId, Balance, ContractError};
+use crate::types::{CounterOffer, Proposal, ProposalId, ProposalStatus, Job, JobId, JobStatus, Address, Balance, ContractError};
 use crate::job_management; // To check job status
-use uapi::{HostFn, HostFnImpl as api};
-
-const MAX_PROPOSALS: usize = 200; // Example limit
-static mut PROPOSALS: [Option<Proposal>; MAX_PROPOSALS] = [None; MAX_PROPOSALS];
-static mut NEXT_PROPOSAL_ID: ProposalId = 0;
-
-fn find_proposal_index(proposal_id: ProposalId) -> Option<usize> {
-    unsafe {
-        for i in 0..MAX_PROPOSALS {
-            if let Some(proposal) = &PROPOSALS[i] {
-                if proposal.id == proposal_id {
-                    return Some(i);
-                }
-            }
-        }
-        None
+use crate::host::Host;
+use crate::storage;
+use crate::events;
+use crate::admin;
+use crate::pagination::{self, MAX_PAGE_SIZE};
+
+const MAX_PROPOSALS: ProposalId = 200; // Example limit
+const MAX_ROUNDS: u32 = 16; // Counter-offers per proposal
+
+/// Submits a new proposal for a job, before the job's bidding deadline.
+/// A freelancer gets one proposal per job; to change the bid use update_proposal.
+/// Withdrawing a proposal frees the slot, so the freelancer may bid again.
+/// The bid must be at least the job's min_budget; bids above its budget are taken but flagged
+/// as over budget (see get_proposal) for the client to decide on.
+/// Input: job_id (JobId), freelancer_id (Address, the caller), bid_amount (Balance), expires_at (u64, 0 = never),
+///        cover_hash ([u8; 32], cover letter digest)
+/// Output: proposal_id (ProposalId) or error code
+pub fn submit_proposal(
+    host: &mut impl Host,
+    job_id: JobId,
+    freelancer_id: Address,
+    bid_amount: Balance,
+    expires_at: u64,
+    cover_hash: [u8; 32],
+) -> Result<ProposalId, ContractError> {
+    admin::ensure_not_paused(host)?;
+    let job = load_job_taking_bids(host, job_id)?;
+    check_bid(&job, bid_amount)?;
+    if holds_proposal(host, job_id, &freelancer_id) {
+        return Err(ContractError::AlreadyExists);
     }
+    if expires_at != 0 && expires_at <= host.now() {
+        return Err(ContractError::InvalidInput); // Would be expired on arrival
+    }
+
+    let proposal_id = storage::next_proposal_id(host);
+    if proposal_id >= MAX_PROPOSALS {
+        return Err(ContractError::StorageFull);
+    }
+
+    let new_proposal = Proposal {
+        id: proposal_id,
+        job_id,
+        freelancer_id,
+        bid_amount,
+        status: ProposalStatus::Submitted,
+        expires_at,
+        cover_hash,
+        round_count: 0,
+    };
+    storage::save_proposal(host, &new_proposal);
+    storage::set_proposal_of(host, job_id, &freelancer_id, proposal_id);
+    storage::set_next_proposal_id(host, proposal_id + 1);
+    events::proposal_submitted(host, &new_proposal);
+    Ok(proposal_id)
 }
 
+/// Changes the bid and cover letter of a proposal the client has not decided on yet.
+/// Only the freelancer who submitted it can, only while the job still takes bids, and only
+/// until the client makes a counter-offer; after that the price is negotiated with counter_offer.
+/// The new bid is checked against the job's budget range like in submit_proposal.
+/// Input: caller (Address), proposal_id (ProposalId), new_bid (Balance), new_cover_hash ([u8; 32])
+/// Output: 0 on success or error code
+pub fn update_proposal(host: &mut impl Host, caller: Address, proposal_id: ProposalId, new_bid: Balance, new_cover_hash: [u8; 32]) -> Result<(), ContractError> {
+    admin::ensure_not_paused(host)?;
+    let mut proposal = load_own_submitted_proposal(host, &caller, proposal_id)?;
+    let job = load_job_taking_bids(host, proposal.job_id)?;
+    if proposal.round_count != 0 {
+        return Err(ContractError::InvalidOperation); // Under negotiation
+    }
+    check_bid(&job, new_bid)?;
 
-/// Submits a new proposal for a job.
-/// Input: job_id (JobId), freelancer_id (UserId), bid_amount (Balance)
-/// Output: proposal_id (ProposalId) or error code
-pub fn submit_proposal(job_id: JobId, freelancer_id: UserId, bid_amount: Balance) -> Result<ProposalId, ContractError> {
-    unsafe {
-        // Check if job exists and is open (simplified check)
-        match job_management::get_job(job_id) {
-            Ok((_, _, status_u8)) => {
-                if status_u8 != crate::types::JobStatus::Open as u8 {
-                    return Err(ContractError::InvalidOperation); // Job not open
-                }
-            },
-            Err(_) => return Err(ContractError::NotFound), // Job not found
-        }
+    proposal.bid_amount = new_bid;
+    proposal.cover_hash = new_cover_hash;
+    storage::save_proposal(host, &proposal);
+    events::proposal_updated(host, &proposal);
+    Ok(())
+}
 
-        if NEXT_PROPOSAL_ID >= MAX_PROPOSALS as ProposalId {
-            return Err(ContractError::StorageFull);
-        }
+/// Retracts a proposal the client has not decided on yet. Freelancer only.
+/// Input: caller (Address), proposal_id (ProposalId)
+/// Output: 0 on success or error code
+pub fn withdraw_proposal(host: &mut impl Host, caller: Address, proposal_id: ProposalId) -> Result<(), ContractError> {
+    admin::ensure_not_paused(host)?;
+    let mut proposal = load_own_submitted_proposal(host, &caller, proposal_id)?;
 
-        let proposal_id = NEXT_PROPOSAL_ID;
-        let new_proposal = Proposal {
-            id: proposal_id,
-            job_id,
-            freelancer_id,
-            bid_amount,
-            status: ProposalStatus::Submitted,
-        };
-
-        let mut stored = false;
-        for i in 0..MAX_PROPOSALS {
-            if PROPOSALS[i].is_none() {
-                PROPOSALS[i] = Some(new_proposal);
-                stored = true;
-                break;
-            }
-        }
-        if !stored {
-            return Err(ContractError::StorageFull);
-        }
+    proposal.status = ProposalStatus::Withdrawn;
+    storage::save_proposal(host, &proposal);
+    events::proposal_status_changed(host, proposal_id, ProposalStatus::Submitted as u8, ProposalStatus::Withdrawn as u8);
+    Ok(())
+}
+
+/// Answers the latest offer on a Submitted proposal with a new amount and terms.
+/// The client opens the negotiation, then the freelancer and the client take turns until
+/// one of them accepts or rejects the other's offer with update_proposal_status.
+/// Every round is kept, see get_counter_offer.
+/// Input: caller (Address), proposal_id (ProposalId), amount (Balance), terms_hash ([u8; 32])
+/// Output: round (u32) or error code
+pub fn counter_offer(host: &mut impl Host, caller: Address, proposal_id: ProposalId, amount: Balance, terms_hash: [u8; 32]) -> Result<u32, ContractError> {
+    admin::ensure_not_paused(host)?;
+    let mut proposal = storage::load_proposal(host, proposal_id).ok_or(ContractError::NotFound(proposal_id))?;
+    if proposal.status != ProposalStatus::Submitted {
+        return Err(ContractError::InvalidOperation); // Already decided or withdrawn
+    }
+    let job = storage::load_job(host, proposal.job_id).ok_or(ContractError::NotFound(proposal.job_id))?;
+    if job.status != JobStatus::Open {
+        return Err(ContractError::InvalidOperation); // Job not open
+    }
+
+    let awaiting_freelancer = awaiting_freelancer(host, &proposal);
+    let expected = if awaiting_freelancer { proposal.freelancer_id } else { job.client_id };
+    if caller != expected {
+        return Err(ContractError::Unauthorized(caller)); // Not their turn
+    }
+    if amount == 0 {
+        return Err(ContractError::InvalidInput);
+    }
+    if caller == proposal.freelancer_id {
+        check_bid(&job, amount)?; // The freelancer's asks stay within the job's range like bids
+    }
+    if bid_expired(host, &proposal) {
+        return Err(ContractError::DeadlinePassed(proposal.expires_at));
+    }
+    if proposal.round_count >= MAX_ROUNDS {
+        return Err(ContractError::StorageFull);
+    }
 
-        NEXT_PROPOSAL_ID += 1;
-        Ok(proposal_id)
+    let offer = CounterOffer { proposal_id, round: proposal.round_count, from: caller, amount, terms_hash };
+    storage::save_counter_offer(host, &offer);
+    proposal.round_count += 1;
+    storage::save_proposal(host, &proposal);
+    events::counter_offered(host, &offer);
+    Ok(offer.round)
+}
+
+/// Gets the state of a proposal's negotiation.
+/// current_amount is the latest counter-offer, or the bid if nobody countered;
+/// once the proposal is Accepted it is the agreed price.
+/// Input: proposal_id (ProposalId)
+/// Output: (round_count, current_amount) or error code
+pub fn get_negotiation(host: &impl Host, proposal_id: ProposalId) -> Result<(u32, Balance), ContractError> {
+    let proposal = storage::load_proposal(host, proposal_id).ok_or(ContractError::NotFound(proposal_id))?;
+    Ok((proposal.round_count, current_amount(host, &proposal)))
+}
+
+/// Gets one round of a proposal's negotiation.
+/// Input: proposal_id (ProposalId), round (u32)
+/// Output: (from, amount, terms_hash) or error code
+pub fn get_counter_offer(host: &impl Host, proposal_id: ProposalId, round: u32) -> Result<(Address, Balance, [u8; 32]), ContractError> {
+    let proposal = storage::load_proposal(host, proposal_id).ok_or(ContractError::NotFound(proposal_id))?;
+    if round >= proposal.round_count {
+        return Err(ContractError::NotFound(round));
     }
+    let offer = storage::load_counter_offer(host, proposal_id, round).ok_or(ContractError::NotFound(round))?;
+    Ok((offer.from, offer.amount, offer.terms_hash))
 }
 
+/// getProposal return values: (job_id, freelancer_id, bid_amount, status_u8, expires_at, cover_hash, over_budget)
+/// over_budget compares the current negotiated amount (see get_negotiation) with the job's budget.
+pub type ProposalView = (JobId, Address, Balance, u8, u64, [u8; 32], bool);
+
 /// Gets proposal details.
 /// Input: proposal_id (ProposalId)
-/// Output: (job_id, freelancer_id, bid_amount, status_u8) or error code
-pub fn get_proposal(proposal_id: ProposalId) -> Result<(JobId, UserId, Balance, u8), ContractError> {
-    unsafe {
-        if let Some(index) = find_proposal_index(proposal_id) {
-            if let Some(proposal) = &PROPOSALS[index] {
-                return Ok((
-                    proposal.job_id,
-                    proposal.freelancer_id,
-                    proposal.bid_amount,
-                    proposal.status as u8,
-                ));
-            }
-        }
-        Err(ContractError::NotFound)
+/// Output: ProposalView or error code
+pub fn get_proposal(host: &impl Host, proposal_id: ProposalId) -> Result<ProposalView, ContractError> {
+    let proposal = storage::load_proposal(host, proposal_id).ok_or(ContractError::NotFound(proposal_id))?;
+    let job = storage::load_job(host, proposal.job_id).ok_or(ContractError::NotFound(proposal.job_id))?;
+    Ok((
+        proposal.job_id,
+        proposal.freelancer_id,
+        proposal.bid_amount,
+        proposal.status as u8,
+        proposal.expires_at,
+        proposal.cover_hash,
+        current_amount(host, &proposal) > job.budget,
+    ))
+}
+
+/// Looks up the proposal a freelancer submitted for a job.
+/// Input: job_id (JobId), freelancer (Address)
+/// Output: (whether there is one, proposal_id) or error code
+pub fn get_proposal_by_freelancer(host: &impl Host, job_id: JobId, freelancer: &Address) -> Result<(bool, ProposalId), ContractError> {
+    storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
+    match storage::proposal_of(host, job_id, freelancer) {
+        Some(proposal_id) => Ok((true, proposal_id)),
+        None => Ok((false, 0)),
+    }
+}
+
+/// Whether `freelancer` has a proposal on `job_id` that rules out another bid,
+/// i.e. one that was not withdrawn.
+pub fn holds_proposal(host: &impl Host, job_id: JobId, freelancer: &Address) -> bool {
+    match storage::proposal_of(host, job_id, freelancer) {
+        Some(proposal_id) => !matches!(
+            storage::load_proposal(host, proposal_id),
+            Some(proposal) if proposal.status == ProposalStatus::Withdrawn
+        ),
+        None => false,
     }
 }
 
-/// Updates a proposal's status (e.g., accept/reject).
-/// Input: proposal_id (ProposalId), new_status_u8 (u8)
+/// Lists the ids of all proposals submitted for a job.
+/// Input: job_id (JobId), offset (u32), limit (u32), page buffer
+/// Output: (total proposals for the job, number of ids written to `page`) or error code
+pub fn list_proposals_for_job(host: &impl Host, job_id: JobId, offset: u32, limit: u32, page: &mut [ProposalId; MAX_PAGE_SIZE]) -> Result<(u32, usize), ContractError> {
+    storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
+    let matches = (0..storage::next_proposal_id(host)).filter(|proposal_id| {
+        matches!(storage::load_proposal(host, *proposal_id), Some(proposal) if proposal.job_id == job_id)
+    });
+    Ok(pagination::paginate(matches, offset, limit, page))
+}
+
+/// Updates a proposal's status (e.g., accept/reject) on behalf of `caller`.
+/// Accepting agrees to the latest offer, see get_negotiation.
+/// Input: caller (Address), proposal_id (ProposalId), new_status_u8 (u8)
 /// Output: 0 on success or error code
-pub fn update_proposal_status(proposal_id: ProposalId, new_status_u8: u8) -> Result<(), ContractError> {
-    unsafe {
-        let new_status = ProposalStatus::from_u8(new_status_u8).ok_or(ContractError::InvalidInput)?;
-
-        if let Some(index) = find_proposal_index(proposal_id) {
-            if let Some(proposal) = &mut PROPOSALS[index] {
-                // Basic state transition validation
-                match (proposal.status, new_status) {
-                    (ProposalStatus::Submitted, ProposalStatus::Accepted) => {},
-                    (ProposalStatus::Submitted, ProposalStatus::Rejected) => {},
-                    _ => return Err(ContractError::InvalidOperation),
-                }
-                proposal.status = new_status;
-                // Potentially trigger agreement creation if accepted (handled in agreement_management)
-                return Ok(());
+pub fn update_proposal_status(host: &mut impl Host, caller: Address, proposal_id: ProposalId, new_status_u8: u8) -> Result<(), ContractError> {
+    admin::ensure_not_paused(host)?;
+    let new_status = ProposalStatus::from_u8(new_status_u8).ok_or(ContractError::InvalidInput)?;
+    let mut proposal = storage::load_proposal(host, proposal_id).ok_or(ContractError::NotFound(proposal_id))?;
+
+    // Basic state transition validation
+    match (proposal.status, new_status) {
+        (ProposalStatus::Submitted, ProposalStatus::Accepted) => {},
+        (ProposalStatus::Submitted, ProposalStatus::Rejected) => {},
+        (from, to) => return Err(ContractError::InvalidTransition(from as u8, to as u8)),
+    }
+
+    let (client_id, _, job_status_u8, ..) = job_management::get_job(host, proposal.job_id)?;
+    if new_status == ProposalStatus::Accepted && job_status_u8 != JobStatus::Open as u8 {
+        return Err(ContractError::InvalidOperation); // Job not open
+    }
+    let awaiting_freelancer = awaiting_freelancer(host, &proposal);
+    ensure_can_update_proposal(&client_id, &proposal.freelancer_id, awaiting_freelancer, &caller, new_status)?;
+
+    // An expired proposal can still be rejected, but no longer accepted
+    if new_status == ProposalStatus::Accepted && bid_expired(host, &proposal) {
+        return Err(ContractError::DeadlinePassed(proposal.expires_at));
+    }
+
+    let old_status = proposal.status;
+    proposal.status = new_status;
+    // Potentially trigger agreement creation if accepted (handled in agreement_management)
+    storage::save_proposal(host, &proposal);
+    events::proposal_status_changed(host, proposal_id, old_status as u8, new_status as u8);
+    Ok(())
+}
+
+/// Rejects every other proposal for `job_id` that is still Submitted or was Accepted
+/// once `accepted` has won the job.
+pub fn reject_competing_proposals(host: &mut impl Host, job_id: JobId, accepted: ProposalId) {
+    for proposal_id in 0..storage::next_proposal_id(host) {
+        match storage::load_proposal(host, proposal_id) {
+            Some(mut proposal)
+                if proposal_id != accepted
+                    && proposal.job_id == job_id
+                    && matches!(proposal.status, ProposalStatus::Submitted | ProposalStatus::Accepted) =>
+            {
+                let old_status = proposal.status;
+                proposal.status = ProposalStatus::Rejected;
+                storage::save_proposal(host, &proposal);
+                events::proposal_status_changed(host, proposal_id, old_status as u8, ProposalStatus::Rejected as u8);
             }
+            _ => {}
         }
-        Err(ContractError::NotFound)
     }
 }
 
-// --- ABI Helper Functions (Simplified) ---
+/// A job takes new or revised bids while it is Open and its bidding deadline has not passed.
+fn load_job_taking_bids(host: &impl Host, job_id: JobId) -> Result<Job, ContractError> {
+    let job = storage::load_job(host, job_id).ok_or(ContractError::NotFound(job_id))?;
+    if job.status != JobStatus::Open {
+        return Err(ContractError::InvalidOperation); // Job not open
+    }
+    if job.bidding_deadline != 0 && host.now() > job.bidding_deadline {
+        return Err(ContractError::DeadlinePassed(job.bidding_deadline));
+    }
+    Ok(job)
+}
 
-/// Decodes submit_proposal arguments: job_id (u32), freelancer_id (u32), bid_amount (u128)
-pub fn decode_submit_proposal_args(data: &[u8]) -> Result<(JobId, UserId, Balance), ContractError> {
-    if data.len() < 4 + 4 + 16 { // selector + job_id + freelancer_id + bid_amount
+/// A bid below the job's min_budget (or zero) is rejected outright. There is no upper limit:
+/// the client may still want an over-budget bid, so get_proposal only flags it.
+fn check_bid(job: &Job, bid: Balance) -> Result<(), ContractError> {
+    if bid == 0 || bid < job.min_budget {
         return Err(ContractError::InvalidInput);
     }
-    let job_id = u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?);
-    let freelancer_id = u32::from_be_bytes(data[4..8].try_into().map_err(|_| ContractError::InvalidInput)?);
-    let bid_amount = u128::from_be_bytes(data[8..24].try_into().map_err(|_| ContractError::InvalidInput)?);
-    Ok((job_id, freelancer_id, bid_amount))
+    Ok(())
 }
 
-/// Decodes get_proposal arguments: proposal_id (u32)
-pub fn decode_get_proposal_args(data: &[u8]) -> Result<ProposalId, ContractError> {
-    if data.len() < 4 { // selector + proposal_id
-        return Err(ContractError::InvalidInput);
+/// Loads a proposal that `caller` submitted and that is still waiting for the client.
+fn load_own_submitted_proposal(host: &impl Host, caller: &Address, proposal_id: ProposalId) -> Result<Proposal, ContractError> {
+    let proposal = storage::load_proposal(host, proposal_id).ok_or(ContractError::NotFound(proposal_id))?;
+    if *caller != proposal.freelancer_id {
+        return Err(ContractError::Unauthorized(*caller));
+    }
+    if proposal.status != ProposalStatus::Submitted {
+        return Err(ContractError::InvalidOperation); // Already decided or withdrawn
     }
-    Ok(u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?))
+    Ok(proposal)
 }
 
-/// Decodes update_proposal_status arguments: proposal_id (u32), new_status (u8)
-pub fn decode_update_proposal_status_args(data: &[u8]) -> Result<(ProposalId, u8), ContractError> {
-    if data.len() < 4 + 1 { // selector + proposal_id + status
-        return Err(ContractError::InvalidInput);
+fn latest_offer(host: &impl Host, proposal: &Proposal) -> Option<CounterOffer> {
+    let round = proposal.round_count.checked_sub(1)?;
+    storage::load_counter_offer(host, proposal.id, round)
+}
+
+fn current_amount(host: &impl Host, proposal: &Proposal) -> Balance {
+    match latest_offer(host, proposal) {
+        Some(offer) => offer.amount,
+        None => proposal.bid_amount,
     }
-    let proposal_id = u32::from_be_bytes(data[0..4].try_into().map_err(|_| ContractError::InvalidInput)?);
-    let status = data[4];
-    Ok((proposal_id, status))
 }
 
-/// Encodes a ProposalId result
-pub fn encode_proposal_id_result(result: Result<ProposalId, ContractError>, output_buffer: &mut [u8; 32]) {
-    match result {
-        Ok(id) => {
-            output_buffer[28..].copy_from_slice(&id.to_be_bytes());
-        }
-        Err(err) => {
-            output_buffer[31] = err as u8;
-        }
+/// Whether the freelancer has to answer a counter-offer from the client.
+fn awaiting_freelancer(host: &impl Host, proposal: &Proposal) -> bool {
+    matches!(latest_offer(host, proposal), Some(offer) if offer.from != proposal.freelancer_id)
+}
+
+/// expires_at only bounds the original bid; once countered, the latest offer stands until answered.
+fn bid_expired(host: &impl Host, proposal: &Proposal) -> bool {
+    proposal.round_count == 0 && proposal.expires_at != 0 && host.now() > proposal.expires_at
+}
+
+/// The client who posted the job decides on its proposals, except that a counter-offer from
+/// the client is accepted or rejected by the freelancer (the client can still reject it).
+/// There is no owner override: accepting a bid commits the client's budget.
+fn ensure_can_update_proposal(
+    job_client: &Address,
+    freelancer: &Address,
+    awaiting_freelancer: bool,
+    caller: &Address,
+    new_status: ProposalStatus,
+) -> Result<(), ContractError> {
+    let allowed = if awaiting_freelancer {
+        caller == freelancer || (caller == job_client && new_status == ProposalStatus::Rejected)
+    } else {
+        caller == job_client
+    };
+    if !allowed {
+        return Err(ContractError::Unauthorized(*caller));
     }
+    Ok(())
 }
 
-/// Encodes a GetProposal result
-pub fn encode_get_proposal_result(result: Result<(JobId, UserId, Balance, u8), ContractError>, output_buffer: &mut [u8; 32]) {
-    match result {
-        Ok((job_id, freelancer_id, bid_amount, status_u8)) => {
-            let mut temp_buf = [0u8; 4 + 4 + 16 + 1];
-            temp_buf[0..4].copy_from_slice(&job_id.to_be_bytes());
-            temp_buf[4..8].copy_from_slice(&freelancer_id.to_be_bytes());
-            temp_buf[8..24].copy_from_slice(&bid_amount.to_be_bytes());
-            temp_buf[24] = status_u8;
-            let offset = 32 - temp_buf.len();
-            output_buffer[offset..].copy_from_slice(&temp_buf);
-        }
-        Err(err) => {
-            output_buffer[31] = err as u8;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{job_params, MockHost};
+    use crate::abi::Word;
+    use crate::types::JobParams;
+
+    const CLIENT: Address = [1u8; 20];
+    const FREELANCER: Address = [2u8; 20];
+    const OWNER: Address = [9u8; 20];
+
+    /// A deployed contract with one Open job (id 0) posted by CLIENT.
+    fn with_open_job() -> MockHost {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 1_000).unwrap();
+        host
+    }
+
+    fn allowed(awaiting_freelancer: bool, caller: Address, new_status: ProposalStatus) -> bool {
+        ensure_can_update_proposal(&CLIENT, &FREELANCER, awaiting_freelancer, &caller, new_status).is_ok()
+    }
+
+    #[test]
+    fn job_client_can_accept_or_reject() {
+        assert!(allowed(false, CLIENT, ProposalStatus::Accepted));
+        assert!(allowed(false, CLIENT, ProposalStatus::Rejected));
+    }
+
+    #[test]
+    fn freelancer_cannot_accept_own_proposal() {
+        assert!(!allowed(false, FREELANCER, ProposalStatus::Accepted));
+    }
+
+    #[test]
+    fn owner_cannot_accept_on_behalf_of_client() {
+        assert!(!allowed(false, OWNER, ProposalStatus::Accepted));
+        assert!(!allowed(true, OWNER, ProposalStatus::Accepted));
+    }
+
+    #[test]
+    fn freelancer_answers_client_counter_offer() {
+        assert!(allowed(true, FREELANCER, ProposalStatus::Accepted));
+        assert!(allowed(true, FREELANCER, ProposalStatus::Rejected));
+        assert!(!allowed(true, CLIENT, ProposalStatus::Accepted));
+        assert!(allowed(true, CLIENT, ProposalStatus::Rejected));
+    }
+
+    #[test]
+    fn submit_proposal_for_open_job() {
+        let mut host = with_open_job();
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]), Ok(0));
+        assert_eq!(submit_proposal(&mut host, 0, OWNER, 900, 0, [0u8; 32]), Ok(1));
+
+        assert_eq!(get_proposal(&host, 0), Ok((0, FREELANCER, 800, ProposalStatus::Submitted as u8, 0, [0u8; 32], false)));
+        assert_eq!(host.events.last().unwrap().topics[0], events::PROPOSAL_SUBMITTED);
+    }
+
+    #[test]
+    fn one_proposal_per_freelancer_and_job() {
+        let mut host = with_open_job();
+        job_management::create_job(&mut host, CLIENT, &job_params(500), 0).unwrap();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 700, 0, [0u8; 32]), Err(ContractError::AlreadyExists));
+        assert_eq!(submit_proposal(&mut host, 1, FREELANCER, 400, 0, [0u8; 32]), Ok(1));
+        assert_eq!(submit_proposal(&mut host, 0, OWNER, 900, 0, [0u8; 32]), Ok(2));
+
+        assert_eq!(get_proposal_by_freelancer(&host, 0, &FREELANCER), Ok((true, 0)));
+        assert_eq!(get_proposal_by_freelancer(&host, 1, &FREELANCER), Ok((true, 1)));
+        assert_eq!(get_proposal_by_freelancer(&host, 1, &OWNER), Ok((false, 0)));
+        assert_eq!(get_proposal_by_freelancer(&host, 2, &OWNER), Err(ContractError::NotFound(2)));
+    }
+
+    #[test]
+    fn withdrawn_proposal_frees_the_slot() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        withdraw_proposal(&mut host, FREELANCER, 0).unwrap();
+
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 700, 0, [0u8; 32]), Ok(1));
+        assert_eq!(get_proposal_by_freelancer(&host, 0, &FREELANCER), Ok((true, 1)));
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 600, 0, [0u8; 32]), Err(ContractError::AlreadyExists));
+    }
+
+    #[test]
+    fn bids_are_checked_against_the_budget_range() {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        let params = JobParams { budget: 1_000, min_budget: 600, ..JobParams::default() };
+        job_management::create_job(&mut host, CLIENT, &params, 0).unwrap();
+
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 599, 0, [0u8; 32]), Err(ContractError::InvalidInput));
+        assert_eq!(submit_proposal(&mut host, 0, OWNER, 0, 0, [0u8; 32]), Err(ContractError::InvalidInput));
+        submit_proposal(&mut host, 0, FREELANCER, 1_000, 0, [0u8; 32]).unwrap();
+        assert!(!get_proposal(&host, 0).unwrap().6);
+
+        // Over budget is the client's call, not the contract's
+        update_proposal(&mut host, FREELANCER, 0, 1_200, [0u8; 32]).unwrap();
+        assert!(get_proposal(&host, 0).unwrap().6);
+        assert_eq!(update_proposal(&mut host, FREELANCER, 0, 500, [0u8; 32]), Err(ContractError::InvalidInput));
+        update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+    }
+
+    #[test]
+    fn negotiated_amount_decides_over_budget() {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        let params = JobParams { budget: 1_000, min_budget: 600, ..JobParams::default() };
+        job_management::create_job(&mut host, CLIENT, &params, 0).unwrap();
+        submit_proposal(&mut host, 0, FREELANCER, 1_200, 0, [0u8; 32]).unwrap();
+        assert!(get_proposal(&host, 0).unwrap().6);
+
+        counter_offer(&mut host, CLIENT, 0, 500, [0u8; 32]).unwrap();
+        assert!(!get_proposal(&host, 0).unwrap().6);
+
+        // The freelancer's counter-offers are held to the job's minimum like bids
+        assert_eq!(counter_offer(&mut host, FREELANCER, 0, 550, [0u8; 32]), Err(ContractError::InvalidInput));
+        counter_offer(&mut host, FREELANCER, 0, 1_100, [0u8; 32]).unwrap();
+        assert!(get_proposal(&host, 0).unwrap().6);
+    }
+
+    #[test]
+    fn submit_proposal_requires_open_job() {
+        let mut host = with_open_job();
+        assert_eq!(submit_proposal(&mut host, 3, FREELANCER, 800, 0, [0u8; 32]), Err(ContractError::NotFound(3)));
+
+        job_management::update_job_status(&mut host, CLIENT, 0, JobStatus::Cancelled as u8).unwrap();
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]), Err(ContractError::InvalidOperation));
+    }
+
+    #[test]
+    fn only_proposals_on_open_jobs_can_be_accepted() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        job_management::update_job_status(&mut host, CLIENT, 0, JobStatus::Cancelled as u8).unwrap();
+
+        let result = update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8);
+        assert_eq!(result, Err(ContractError::InvalidOperation));
+        update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Rejected as u8).unwrap();
+    }
+
+    #[test]
+    fn client_accepts_and_rejects_proposals() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        submit_proposal(&mut host, 0, OWNER, 900, 0, [0u8; 32]).unwrap();
+
+        update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+        update_proposal_status(&mut host, CLIENT, 1, ProposalStatus::Rejected as u8).unwrap();
+        assert_eq!(get_proposal(&host, 0).unwrap().3, ProposalStatus::Accepted as u8);
+        assert_eq!(get_proposal(&host, 1).unwrap().3, ProposalStatus::Rejected as u8);
+    }
+
+    #[test]
+    fn freelancer_cannot_update_proposal_status() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+
+        let result = update_proposal_status(&mut host, FREELANCER, 0, ProposalStatus::Accepted as u8);
+        assert_eq!(result, Err(ContractError::Unauthorized(FREELANCER)));
+        assert_eq!(get_proposal(&host, 0).unwrap().3, ProposalStatus::Submitted as u8);
+    }
+
+    #[test]
+    fn decided_proposals_are_final() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Rejected as u8).unwrap();
+
+        let result = update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8);
+        assert_eq!(result, Err(ContractError::InvalidTransition(2, 1)));
+    }
+
+    #[test]
+    fn freelancer_withdraws_undecided_proposal() {
+        let mut host = with_open_job();
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 0).unwrap();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        submit_proposal(&mut host, 1, FREELANCER, 900, 0, [0u8; 32]).unwrap();
+        update_proposal_status(&mut host, CLIENT, 1, ProposalStatus::Rejected as u8).unwrap();
+
+        assert_eq!(withdraw_proposal(&mut host, CLIENT, 0), Err(ContractError::Unauthorized(CLIENT)));
+        assert_eq!(withdraw_proposal(&mut host, FREELANCER, 1), Err(ContractError::InvalidOperation));
+        withdraw_proposal(&mut host, FREELANCER, 0).unwrap();
+        assert_eq!(get_proposal(&host, 0).unwrap().3, ProposalStatus::Withdrawn as u8);
+        assert_eq!(host.events.last().unwrap().data[32..], (ProposalStatus::Withdrawn as u8).to_word());
+
+        // Withdrawn is final for the client too
+        let result = update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8);
+        assert_eq!(result, Err(ContractError::InvalidTransition(3, 1)));
+        assert_eq!(withdraw_proposal(&mut host, FREELANCER, 0), Err(ContractError::InvalidOperation));
+    }
+
+    #[test]
+    fn freelancer_revises_bid_while_job_is_open() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0xaa; 32]).unwrap();
+
+        assert_eq!(update_proposal(&mut host, OWNER, 0, 700, [0xbb; 32]), Err(ContractError::Unauthorized(OWNER)));
+        update_proposal(&mut host, FREELANCER, 0, 700, [0xbb; 32]).unwrap();
+        assert_eq!(get_proposal(&host, 0), Ok((0, FREELANCER, 700, ProposalStatus::Submitted as u8, 0, [0xbb; 32], false)));
+        let event = host.events.last().unwrap();
+        assert_eq!(event.topics, vec![events::PROPOSAL_UPDATED, 0u32.to_word()]);
+        assert_eq!(event.data[32..], [0xbb; 32]);
+
+        job_management::update_job_status(&mut host, CLIENT, 0, JobStatus::Cancelled as u8).unwrap();
+        assert_eq!(update_proposal(&mut host, FREELANCER, 0, 600, [0xbb; 32]), Err(ContractError::InvalidOperation));
+    }
+
+    #[test]
+    fn decided_proposals_cannot_be_revised() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+
+        assert_eq!(update_proposal(&mut host, FREELANCER, 0, 1, [0u8; 32]), Err(ContractError::InvalidOperation));
+        assert_eq!(update_proposal(&mut host, FREELANCER, 5, 1, [0u8; 32]), Err(ContractError::NotFound(5)));
+        assert_eq!(get_proposal(&host, 0).unwrap().2, 800);
+    }
+
+    #[test]
+    fn client_and_freelancer_take_turns_countering() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 900, 0, [0u8; 32]).unwrap();
+
+        // The client opens, then the sides alternate
+        assert_eq!(counter_offer(&mut host, FREELANCER, 0, 850, [0u8; 32]), Err(ContractError::Unauthorized(FREELANCER)));
+        assert_eq!(counter_offer(&mut host, CLIENT, 0, 600, [0xaa; 32]), Ok(0));
+        assert_eq!(counter_offer(&mut host, CLIENT, 0, 650, [0xaa; 32]), Err(ContractError::Unauthorized(CLIENT)));
+        assert_eq!(counter_offer(&mut host, FREELANCER, 0, 0, [0xbb; 32]), Err(ContractError::InvalidInput));
+        assert_eq!(counter_offer(&mut host, FREELANCER, 0, 750, [0xbb; 32]), Ok(1));
+        assert_eq!(counter_offer(&mut host, CLIENT, 0, 700, [0xcc; 32]), Ok(2));
+        let event = host.events.last().unwrap();
+        assert_eq!(event.topics, vec![events::COUNTER_OFFERED, 0u32.to_word(), CLIENT.to_word()]);
+
+        assert_eq!(get_negotiation(&host, 0), Ok((3, 700)));
+        assert_eq!(get_counter_offer(&host, 0, 0), Ok((CLIENT, 600, [0xaa; 32])));
+        assert_eq!(get_counter_offer(&host, 0, 1), Ok((FREELANCER, 750, [0xbb; 32])));
+        assert_eq!(get_counter_offer(&host, 0, 3), Err(ContractError::NotFound(3)));
+        assert_eq!(get_proposal(&host, 0).unwrap().2, 900); // The original bid is kept
+
+        // The bid is no longer the freelancer's to revise
+        assert_eq!(update_proposal(&mut host, FREELANCER, 0, 800, [0u8; 32]), Err(ContractError::InvalidOperation));
+
+        // The client's own counter-offer is the freelancer's to accept
+        let result = update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8);
+        assert_eq!(result, Err(ContractError::Unauthorized(CLIENT)));
+        update_proposal_status(&mut host, FREELANCER, 0, ProposalStatus::Accepted as u8).unwrap();
+        assert_eq!(get_proposal(&host, 0).unwrap().3, ProposalStatus::Accepted as u8);
+        assert_eq!(counter_offer(&mut host, FREELANCER, 0, 800, [0u8; 32]), Err(ContractError::InvalidOperation));
+    }
+
+    #[test]
+    fn freelancer_rejects_counter_offer() {
+        let mut host = with_open_job();
+        submit_proposal(&mut host, 0, FREELANCER, 900, 0, [0u8; 32]).unwrap();
+        assert_eq!(get_negotiation(&host, 0), Ok((0, 900)));
+        counter_offer(&mut host, CLIENT, 0, 500, [0u8; 32]).unwrap();
+
+        update_proposal_status(&mut host, FREELANCER, 0, ProposalStatus::Rejected as u8).unwrap();
+        assert_eq!(get_proposal(&host, 0).unwrap().3, ProposalStatus::Rejected as u8);
+    }
+
+    #[test]
+    fn expired_bids_cannot_be_countered() {
+        let mut host = with_open_job();
+        host.timestamp = 1_000;
+        submit_proposal(&mut host, 0, OWNER, 900, 1_500, [0u8; 32]).unwrap();
+        submit_proposal(&mut host, 0, FREELANCER, 900, 1_500, [0u8; 32]).unwrap();
+        counter_offer(&mut host, CLIENT, 1, 800, [0u8; 32]).unwrap();
+
+        host.timestamp = 1_501;
+        assert_eq!(counter_offer(&mut host, CLIENT, 0, 800, [0u8; 32]), Err(ContractError::DeadlinePassed(1_500)));
+        // A negotiation under way is not cut off by the bid's expiry
+        counter_offer(&mut host, FREELANCER, 1, 850, [0u8; 32]).unwrap();
+        update_proposal_status(&mut host, CLIENT, 1, ProposalStatus::Accepted as u8).unwrap();
+    }
+
+    #[test]
+    fn list_proposals_only_returns_the_jobs_proposals() {
+        let mut host = with_open_job();
+        job_management::create_job(&mut host, CLIENT, &job_params(500), 0).unwrap();
+        submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        submit_proposal(&mut host, 1, FREELANCER, 400, 0, [0u8; 32]).unwrap();
+        submit_proposal(&mut host, 0, OWNER, 900, 0, [0u8; 32]).unwrap();
+        let mut page = [0; MAX_PAGE_SIZE];
+
+        assert_eq!(list_proposals_for_job(&host, 0, 0, 10, &mut page), Ok((2, 2)));
+        assert_eq!(page[..2], [0, 2]);
+        assert_eq!(list_proposals_for_job(&host, 1, 0, 10, &mut page), Ok((1, 1)));
+        assert_eq!(page[0], 1);
+        assert_eq!(list_proposals_for_job(&host, 2, 0, 10, &mut page), Err(ContractError::NotFound(2)));
+    }
+
+    #[test]
+    fn bids_close_at_bidding_deadline() {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        host.timestamp = 1_000;
+        let params = JobParams { budget: 1_000, bidding_deadline: 2_000, ..JobParams::default() };
+        job_management::create_job(&mut host, CLIENT, &params, 0).unwrap();
+
+        host.timestamp = 2_000;
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]), Ok(0));
+        host.timestamp = 2_001;
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]), Err(ContractError::DeadlinePassed(2_000)));
+    }
+
+    #[test]
+    fn expired_proposals_cannot_be_accepted() {
+        let mut host = with_open_job();
+        host.timestamp = 1_000;
+        assert_eq!(submit_proposal(&mut host, 0, FREELANCER, 800, 1_000, [0u8; 32]), Err(ContractError::InvalidInput));
+        submit_proposal(&mut host, 0, FREELANCER, 800, 1_500, [0u8; 32]).unwrap();
+        submit_proposal(&mut host, 0, OWNER, 900, 1_500, [0u8; 32]).unwrap();
+        assert_eq!(get_proposal(&host, 0).unwrap().4, 1_500);
+
+        host.timestamp = 1_501;
+        let result = update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8);
+        assert_eq!(result, Err(ContractError::DeadlinePassed(1_500)));
+        update_proposal_status(&mut host, CLIENT, 1, ProposalStatus::Rejected as u8).unwrap();
     }
 }
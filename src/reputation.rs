@@ -0,0 +1,167 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/reputation.rs
+
+// Two-sided ratings and per-account reputation.
+// Once an agreement is Completed, the client and the freelancer may each rate the other
+// exactly once. Completed jobs and disputes are counted as agreements reach those states.
+
+use crate::types::{Address, Agreement, AgreementId, AgreementStatus, ContractError, Reputation};
+use crate::host::Host;
+use crate::storage;
+use crate::events;
+use crate::admin;
+
+// Which party of an agreement left a review
+pub const CLIENT_SIDE: u32 = 0;
+pub const FREELANCER_SIDE: u32 = 1;
+
+pub const MIN_SCORE: u8 = 1;
+pub const MAX_SCORE: u8 = 5;
+
+/// Rates the other party of a Completed agreement.
+/// Input: caller (Address), agreement_id (AgreementId), score (1-5), review_hash ([u8; 32])
+/// Output: 0 on success or error code
+pub fn rate_counterparty(host: &mut impl Host, caller: Address, agreement_id: AgreementId, score: u8, review_hash: [u8; 32]) -> Result<(), ContractError> {
+    admin::ensure_not_paused(host)?;
+    if !(MIN_SCORE..=MAX_SCORE).contains(&score) {
+        return Err(ContractError::InvalidInput);
+    }
+    let agreement = storage::load_agreement(host, agreement_id).ok_or(ContractError::NotFound(agreement_id))?;
+    if agreement.status != AgreementStatus::Completed {
+        return Err(ContractError::InvalidOperation); // Nothing to rate yet
+    }
+
+    let (side, reviewee) = if caller == agreement.client_id {
+        (CLIENT_SIDE, agreement.freelancer_id)
+    } else if caller == agreement.freelancer_id {
+        (FREELANCER_SIDE, agreement.client_id)
+    } else {
+        return Err(ContractError::Unauthorized(caller));
+    };
+    if storage::load_review(host, agreement_id, side).is_some() {
+        return Err(ContractError::AlreadyExists);
+    }
+
+    storage::save_review(host, agreement_id, side, score, &review_hash);
+    update(host, &reviewee, |reputation| {
+        reputation.rating_count += 1;
+        reputation.rating_sum += score as u32;
+    });
+    events::review_submitted(host, agreement_id, &caller, &reviewee, score, &review_hash);
+    Ok(())
+}
+
+/// Gets the aggregate reputation of an account.
+/// Input: account (Address)
+/// Output: (rating_count, rating_sum, completed_jobs, disputes)
+pub fn get_reputation(host: &impl Host, account: &Address) -> (u32, u32, u32, u32) {
+    let reputation = storage::load_reputation(host, account);
+    (reputation.rating_count, reputation.rating_sum, reputation.completed_jobs, reputation.disputes)
+}
+
+/// Gets the review `reviewer` left on an agreement.
+/// Input: agreement_id (AgreementId), reviewer (Address)
+/// Output: (score, review_hash); score 0 means no review yet
+pub fn get_review(host: &impl Host, agreement_id: AgreementId, reviewer: &Address) -> Result<(u8, [u8; 32]), ContractError> {
+    let agreement = storage::load_agreement(host, agreement_id).ok_or(ContractError::NotFound(agreement_id))?;
+    let side = if *reviewer == agreement.client_id {
+        CLIENT_SIDE
+    } else if *reviewer == agreement.freelancer_id {
+        FREELANCER_SIDE
+    } else {
+        return Err(ContractError::InvalidInput); // Not a party to this agreement
+    };
+    Ok(storage::load_review(host, agreement_id, side).unwrap_or((0, [0u8; 32])))
+}
+
+/// Counts a completed job for both parties of `agreement`.
+pub fn record_completion(host: &mut impl Host, agreement: &Agreement) {
+    for account in [agreement.client_id, agreement.freelancer_id] {
+        update(host, &account, |reputation| reputation.completed_jobs += 1);
+    }
+}
+
+/// Counts a dispute for both parties of `agreement`.
+pub fn record_dispute(host: &mut impl Host, agreement: &Agreement) {
+    for account in [agreement.client_id, agreement.freelancer_id] {
+        update(host, &account, |reputation| reputation.disputes += 1);
+    }
+}
+
+fn update(host: &mut impl Host, account: &Address, change: impl FnOnce(&mut Reputation)) {
+    let mut reputation = storage::load_reputation(host, account);
+    change(&mut reputation);
+    storage::save_reputation(host, account, &reputation);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{job_params, MockHost};
+    use crate::types::ProposalStatus;
+    use crate::{agreement_management, job_management, proposal_management};
+
+    const CLIENT: Address = [1u8; 20];
+    const FREELANCER: Address = [2u8; 20];
+    const OWNER: Address = [9u8; 20];
+    const STRANGER: Address = [7u8; 20];
+
+    /// Agreement 0 between CLIENT and FREELANCER with a single 800 milestone, still Active.
+    fn with_agreement() -> MockHost {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 1_000).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+        agreement_management::create_agreement_from_proposal(&mut host, CLIENT, 0, &[]).unwrap();
+        host
+    }
+
+    fn completed() -> MockHost {
+        let mut host = with_agreement();
+        agreement_management::submit_milestone(&mut host, FREELANCER, 0, 0).unwrap();
+        agreement_management::approve_milestone(&mut host, CLIENT, 0, 0).unwrap();
+        agreement_management::release_milestone(&mut host, CLIENT, 0, 0).unwrap();
+        host
+    }
+
+    #[test]
+    fn both_parties_rate_each_other_once() {
+        let mut host = completed();
+        rate_counterparty(&mut host, CLIENT, 0, 5, [0xaa; 32]).unwrap();
+        rate_counterparty(&mut host, FREELANCER, 0, 3, [0xbb; 32]).unwrap();
+        assert_eq!(rate_counterparty(&mut host, CLIENT, 0, 1, [0u8; 32]), Err(ContractError::AlreadyExists));
+
+        assert_eq!(get_reputation(&host, &FREELANCER), (1, 5, 1, 0));
+        assert_eq!(get_reputation(&host, &CLIENT), (1, 3, 1, 0));
+        assert_eq!(get_review(&host, 0, &CLIENT), Ok((5, [0xaa; 32])));
+        assert_eq!(get_review(&host, 0, &FREELANCER), Ok((3, [0xbb; 32])));
+        assert_eq!(host.events.last().unwrap().topics[0], events::REVIEW_SUBMITTED);
+    }
+
+    #[test]
+    fn rating_requires_completed_agreement() {
+        let mut host = with_agreement();
+        assert_eq!(rate_counterparty(&mut host, CLIENT, 0, 4, [0u8; 32]), Err(ContractError::InvalidOperation));
+        assert_eq!(rate_counterparty(&mut host, CLIENT, 1, 4, [0u8; 32]), Err(ContractError::NotFound(1)));
+        assert_eq!(get_review(&host, 0, &CLIENT), Ok((0, [0u8; 32])));
+    }
+
+    #[test]
+    fn rating_checks_score_and_parties() {
+        let mut host = completed();
+        assert_eq!(rate_counterparty(&mut host, CLIENT, 0, 0, [0u8; 32]), Err(ContractError::InvalidInput));
+        assert_eq!(rate_counterparty(&mut host, CLIENT, 0, 6, [0u8; 32]), Err(ContractError::InvalidInput));
+        assert_eq!(rate_counterparty(&mut host, STRANGER, 0, 5, [0u8; 32]), Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(get_review(&host, 0, &STRANGER), Err(ContractError::InvalidInput));
+        assert_eq!(get_reputation(&host, &FREELANCER), (0, 0, 1, 0));
+    }
+
+    #[test]
+    fn disputes_count_against_both_parties() {
+        let mut host = with_agreement();
+        agreement_management::update_agreement_status(&mut host, FREELANCER, 0, AgreementStatus::Disputed as u8).unwrap();
+        assert_eq!(get_reputation(&host, &CLIENT), (0, 0, 0, 1));
+        assert_eq!(get_reputation(&host, &FREELANCER), (0, 0, 0, 1));
+        assert_eq!(get_reputation(&host, &STRANGER), (0, 0, 0, 0));
+    }
+}
@@ -0,0 +1,543 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/storage.rs
+
+use crate::types::{
+    Address, Agreement, AgreementId, AgreementStatus, Balance, Config, CounterOffer, Job, JobId, JobStatus, Milestone,
+    MilestoneIndex, MilestoneStatus, Proposal, ProposalId, ProposalStatus, Reputation,
+};
+use crate::host::Host;
+
+// Key scheme
+// ----------
+// Every entry lives under a fixed 32-byte key:
+//   key[0]      = record prefix (see below)
+//   key[1]      = slot selector (counters and config only, zero otherwise)
+//   key[24..28] = parent id, big-endian (milestones: the agreement id, counter-offers: the proposal id)
+//   key[28..32] = record id, big-endian (for milestones and counter-offers, the index within the parent)
+// Per-account records put the 20-byte address in key[12..32] instead of an id.
+// The proposal index also puts the job id in key[8..12], in front of the freelancer's address.
+// All other bytes are zero.
+const PREFIX_JOB: u8 = 0x01;
+const PREFIX_PROPOSAL: u8 = 0x02;
+const PREFIX_AGREEMENT: u8 = 0x03;
+const PREFIX_ESCROW: u8 = 0x04;
+const PREFIX_MILESTONE: u8 = 0x05;
+const PREFIX_REPUTATION: u8 = 0x06;
+const PREFIX_REVIEW: u8 = 0x07;
+const PREFIX_TREASURY: u8 = 0x08;
+const PREFIX_COUNTER_OFFER: u8 = 0x09;
+const PREFIX_PROPOSAL_INDEX: u8 = 0x0a;
+const PREFIX_COUNTER: u8 = 0x10;
+const PREFIX_CONFIG: u8 = 0x11;
+
+const COUNTER_NEXT_JOB_ID: u8 = 0x01;
+const COUNTER_NEXT_PROPOSAL_ID: u8 = 0x02;
+const COUNTER_NEXT_AGREEMENT_ID: u8 = 0x03;
+
+const CONFIG_OWNER: u8 = 0x01;
+const CONFIG_PENDING_OWNER: u8 = 0x02;
+const CONFIG_PARAMS: u8 = 0x03;
+const CONFIG_PAUSED: u8 = 0x04;
+const CONFIG_STORAGE_VERSION: u8 = 0x05;
+const CONFIG_MIGRATION_CURSOR: u8 = 0x06;
+
+/// Layout version written into every job, proposal and agreement record.
+/// Version 1 is the unversioned layout from before in-place upgrades, version 2 added the
+/// version byte, version 3 added fee_bps to agreements, version 4 payment_token to jobs,
+/// version 5 cover_hash and version 6 round_count to proposals, and version 7 min_budget to jobs.
+/// Bump this whenever one of those layouts changes, and teach the matching load function
+/// to decode the previous version.
+pub const STORAGE_VERSION: u8 = 7;
+
+// Byte layouts (all integers big-endian)
+// ------------
+// Jobs, proposals and agreements are stored as version(1) | fields below, see STORAGE_VERSION.
+// Job:       id(4) | client_id(20) | budget(16) | status(1) | content_cid(32) | category(4) | deadline(8)
+//            | bidding_deadline(8) | payment_token(20) | min_budget(16)
+// Proposal:  id(4) | job_id(4) | freelancer_id(20) | bid_amount(16) | status(1) | expires_at(8) | cover_hash(32)
+//            | round_count(4)
+// Agreement: id(4) | job_id(4) | client_id(20) | freelancer_id(20) | total_amount(16) | status(1) | milestone_count(4)
+//            | fee_bps(2)
+// Milestone: amount(16) | description_hash(32) | status(1)
+// Counter-offer: from(20) | amount(16) | terms_hash(32)
+// Proposal index: proposal_id(4), keyed by (job id, freelancer address)
+// Reputation: rating_count(4) | rating_sum(4) | completed_jobs(4) | disputes(4), keyed by address
+// Review:    score(1) | review_hash(32), keyed by (agreement id, rater side)
+// Escrow:    balance(16), keyed by job id
+// Counters:  value(4)
+// Owner, pending owner: address(20)
+// Storage version: version(1). Migration cursor: record kind(1) | next id(4)
+// Treasury:  balance(16), keyed by token address (zero for the native token)
+// Config:    arbitrator(20) | fee_bps(2) | min_job_budget(16) | max_milestones(4)
+const JOB_LEN: usize = 4 + 20 + 16 + 1 + 32 + 4 + 8 + 8 + 20 + 16;
+const JOB_V6_LEN: usize = JOB_LEN - 16; // Versions 4 to 6, before min_budget
+const JOB_V3_LEN: usize = JOB_V6_LEN - 20; // Versions 1 to 3, before payment_token
+const PROPOSAL_LEN: usize = 4 + 4 + 20 + 16 + 1 + 8 + 32 + 4;
+const PROPOSAL_V5_LEN: usize = PROPOSAL_LEN - 4; // Version 5, before round_count
+const PROPOSAL_V4_LEN: usize = PROPOSAL_V5_LEN - 32; // Versions 1 to 4, before cover_hash
+const AGREEMENT_LEN: usize = 4 + 4 + 20 + 20 + 16 + 1 + 4 + 2;
+const AGREEMENT_V2_LEN: usize = AGREEMENT_LEN - 2; // Versions 1 and 2, before fee_bps
+const MILESTONE_LEN: usize = 16 + 32 + 1;
+const COUNTER_OFFER_LEN: usize = 20 + 16 + 32;
+const PROPOSAL_INDEX_LEN: usize = 4;
+const REPUTATION_LEN: usize = 4 * 4;
+const REVIEW_LEN: usize = 1 + 32;
+const ESCROW_LEN: usize = 16;
+const COUNTER_LEN: usize = 4;
+const ADDRESS_LEN: usize = 20;
+const CONFIG_LEN: usize = 20 + 2 + 16 + 4;
+
+const VERSION_LEN: usize = 1;
+const CURSOR_LEN: usize = 1 + 4;
+
+// Largest record we ever read back (including its version byte), used to size the read buffer.
+const MAX_RECORD_LEN: usize = max(JOB_LEN, AGREEMENT_LEN) + VERSION_LEN;
+
+const fn max(a: usize, b: usize) -> usize {
+    if a > b { a } else { b }
+}
+
+fn record_key(prefix: u8, id: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key[0] = prefix;
+    key[28..].copy_from_slice(&id.to_be_bytes());
+    key
+}
+
+fn child_key(prefix: u8, parent_id: u32, id: u32) -> [u8; 32] {
+    let mut key = record_key(prefix, id);
+    key[24..28].copy_from_slice(&parent_id.to_be_bytes());
+    key
+}
+
+fn address_key(prefix: u8, address: &Address) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key[0] = prefix;
+    key[12..].copy_from_slice(address);
+    key
+}
+
+fn job_address_key(prefix: u8, job_id: JobId, address: &Address) -> [u8; 32] {
+    let mut key = address_key(prefix, address);
+    key[8..12].copy_from_slice(&job_id.to_be_bytes());
+    key
+}
+
+fn slot_key(prefix: u8, slot: u8) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key[0] = prefix;
+    key[1] = slot;
+    key
+}
+
+/// Reads the raw value under `key` into `buf`.
+/// Returns the stored bytes, or None if the key is unset or the length is unexpected.
+fn read<'a>(host: &impl Host, key: &[u8; 32], buf: &'a mut [u8; MAX_RECORD_LEN], expected_len: usize) -> Option<&'a [u8]> {
+    let len = host.get_storage(key, buf)?;
+    if len != expected_len {
+        return None;
+    }
+    Some(&buf[..len])
+}
+
+fn write(host: &mut impl Host, key: &[u8; 32], value: &[u8]) {
+    host.set_storage(key, value);
+}
+
+/// Reads a versioned record and returns (layout version, fields).
+/// Version 1 records predate the version byte and are recognised by their length, `v1_len`.
+/// The load functions decode every version they know, so the next save rewrites old records.
+fn read_record<'a>(host: &impl Host, key: &[u8; 32], buf: &'a mut [u8; MAX_RECORD_LEN], v1_len: usize) -> Option<(u8, &'a [u8])> {
+    let len = host.get_storage(key, buf)?;
+    if len == v1_len {
+        return Some((1, &buf[..len]));
+    }
+    if len < VERSION_LEN || !(2..=STORAGE_VERSION).contains(&buf[0]) {
+        return None;
+    }
+    Some((buf[0], &buf[VERSION_LEN..len]))
+}
+
+/// Writes `fields` behind the current version byte.
+fn write_record(host: &mut impl Host, key: &[u8; 32], fields: &[u8]) {
+    let mut data = [0u8; MAX_RECORD_LEN];
+    data[0] = STORAGE_VERSION;
+    data[VERSION_LEN..VERSION_LEN + fields.len()].copy_from_slice(fields);
+    write(host, key, &data[..VERSION_LEN + fields.len()]);
+}
+
+fn read_u32(data: &[u8], at: usize) -> u32 {
+    u32::from_be_bytes(data[at..at + 4].try_into().unwrap())
+}
+
+fn read_u128(data: &[u8], at: usize) -> Balance {
+    u128::from_be_bytes(data[at..at + 16].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], at: usize) -> u64 {
+    u64::from_be_bytes(data[at..at + 8].try_into().unwrap())
+}
+
+fn read_address(data: &[u8], at: usize) -> Address {
+    data[at..at + 20].try_into().unwrap()
+}
+
+// --- Counters ---
+
+fn load_counter(host: &impl Host, counter: u8) -> u32 {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    match read(host, &slot_key(PREFIX_COUNTER, counter), &mut buf, COUNTER_LEN) {
+        Some(data) => read_u32(data, 0),
+        None => 0, // Unset counters start at zero
+    }
+}
+
+fn save_counter(host: &mut impl Host, counter: u8, value: u32) {
+    write(host, &slot_key(PREFIX_COUNTER, counter), &value.to_be_bytes());
+}
+
+pub fn next_job_id(host: &impl Host) -> JobId {
+    load_counter(host, COUNTER_NEXT_JOB_ID)
+}
+
+pub fn set_next_job_id(host: &mut impl Host, value: JobId) {
+    save_counter(host, COUNTER_NEXT_JOB_ID, value)
+}
+
+pub fn next_proposal_id(host: &impl Host) -> ProposalId {
+    load_counter(host, COUNTER_NEXT_PROPOSAL_ID)
+}
+
+pub fn set_next_proposal_id(host: &mut impl Host, value: ProposalId) {
+    save_counter(host, COUNTER_NEXT_PROPOSAL_ID, value)
+}
+
+pub fn next_agreement_id(host: &impl Host) -> AgreementId {
+    load_counter(host, COUNTER_NEXT_AGREEMENT_ID)
+}
+
+pub fn set_next_agreement_id(host: &mut impl Host, value: AgreementId) {
+    save_counter(host, COUNTER_NEXT_AGREEMENT_ID, value)
+}
+
+// --- Config ---
+
+/// The contract owner, set in `deploy()` and changed through the two-step transfer.
+/// Returns the zero address if it was never set.
+pub fn owner(host: &impl Host) -> Address {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    match read(host, &slot_key(PREFIX_CONFIG, CONFIG_OWNER), &mut buf, ADDRESS_LEN) {
+        Some(data) => read_address(data, 0),
+        None => [0u8; 20],
+    }
+}
+
+pub fn set_owner(host: &mut impl Host, owner: &Address) {
+    write(host, &slot_key(PREFIX_CONFIG, CONFIG_OWNER), owner);
+}
+
+/// The account a pending ownership transfer is waiting on.
+/// Returns the zero address if there is none.
+pub fn pending_owner(host: &impl Host) -> Address {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    match read(host, &slot_key(PREFIX_CONFIG, CONFIG_PENDING_OWNER), &mut buf, ADDRESS_LEN) {
+        Some(data) => read_address(data, 0),
+        None => [0u8; 20],
+    }
+}
+
+pub fn set_pending_owner(host: &mut impl Host, pending_owner: &Address) {
+    write(host, &slot_key(PREFIX_CONFIG, CONFIG_PENDING_OWNER), pending_owner);
+}
+
+/// The tunable parameters, or None before `deploy()` has stored them.
+pub fn load_config(host: &impl Host) -> Option<Config> {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    let data = read(host, &slot_key(PREFIX_CONFIG, CONFIG_PARAMS), &mut buf, CONFIG_LEN)?;
+    Some(Config {
+        arbitrator: read_address(data, 0),
+        fee_bps: u16::from_be_bytes([data[20], data[21]]),
+        min_job_budget: read_u128(data, 22),
+        max_milestones: read_u32(data, 38),
+    })
+}
+
+pub fn save_config(host: &mut impl Host, config: &Config) {
+    let mut data = [0u8; CONFIG_LEN];
+    data[0..20].copy_from_slice(&config.arbitrator);
+    data[20..22].copy_from_slice(&config.fee_bps.to_be_bytes());
+    data[22..38].copy_from_slice(&config.min_job_budget.to_be_bytes());
+    data[38..42].copy_from_slice(&config.max_milestones.to_be_bytes());
+    write(host, &slot_key(PREFIX_CONFIG, CONFIG_PARAMS), &data);
+}
+
+/// Whether the owner has paused the contract. Unset means not paused.
+pub fn is_paused(host: &impl Host) -> bool {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    matches!(read(host, &slot_key(PREFIX_CONFIG, CONFIG_PAUSED), &mut buf, 1), Some([1]))
+}
+
+pub fn set_paused(host: &mut impl Host, paused: bool) {
+    write(host, &slot_key(PREFIX_CONFIG, CONFIG_PAUSED), &[paused as u8]);
+}
+
+/// Layout version of the stored records as a whole.
+/// Unset means version 1: the contract was deployed before versioning existed.
+pub fn storage_version(host: &impl Host) -> u8 {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    match read(host, &slot_key(PREFIX_CONFIG, CONFIG_STORAGE_VERSION), &mut buf, VERSION_LEN) {
+        Some(data) => data[0],
+        None => 1,
+    }
+}
+
+pub fn set_storage_version(host: &mut impl Host, version: u8) {
+    write(host, &slot_key(PREFIX_CONFIG, CONFIG_STORAGE_VERSION), &[version]);
+}
+
+/// Where a batched migration left off: (record kind, next id). Starts at (0, 0).
+pub fn migration_cursor(host: &impl Host) -> (u8, u32) {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    match read(host, &slot_key(PREFIX_CONFIG, CONFIG_MIGRATION_CURSOR), &mut buf, CURSOR_LEN) {
+        Some(data) => (data[0], read_u32(data, 1)),
+        None => (0, 0),
+    }
+}
+
+pub fn set_migration_cursor(host: &mut impl Host, kind: u8, next_id: u32) {
+    let mut data = [0u8; CURSOR_LEN];
+    data[0] = kind;
+    data[1..5].copy_from_slice(&next_id.to_be_bytes());
+    write(host, &slot_key(PREFIX_CONFIG, CONFIG_MIGRATION_CURSOR), &data);
+}
+
+/// Platform fees collected in `token` and not yet withdrawn.
+pub fn treasury_balance(host: &impl Host, token: &Address) -> Balance {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    match read(host, &address_key(PREFIX_TREASURY, token), &mut buf, ESCROW_LEN) {
+        Some(data) => read_u128(data, 0),
+        None => 0,
+    }
+}
+
+pub fn set_treasury_balance(host: &mut impl Host, token: &Address, balance: Balance) {
+    write(host, &address_key(PREFIX_TREASURY, token), &balance.to_be_bytes());
+}
+
+// --- Jobs ---
+
+pub fn load_job(host: &impl Host, job_id: JobId) -> Option<Job> {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    let (version, data) = read_record(host, &record_key(PREFIX_JOB, job_id), &mut buf, JOB_V3_LEN)?;
+    let (payment_token, min_budget) = match (version, data.len()) {
+        (1..=3, JOB_V3_LEN) => ([0u8; 20], 0), // Native token only before version 4
+        (4..=6, JOB_V6_LEN) => (read_address(data, 93), 0), // No bid floor before version 7
+        (7.., JOB_LEN) => (read_address(data, 93), read_u128(data, 113)),
+        _ => return None,
+    };
+    Some(Job {
+        id: read_u32(data, 0),
+        client_id: read_address(data, 4),
+        budget: read_u128(data, 24),
+        status: JobStatus::from_u8(data[40])?,
+        content_cid: data[41..73].try_into().unwrap(),
+        category: read_u32(data, 73),
+        deadline: read_u64(data, 77),
+        bidding_deadline: read_u64(data, 85),
+        payment_token,
+        min_budget,
+    })
+}
+
+pub fn save_job(host: &mut impl Host, job: &Job) {
+    let mut data = [0u8; JOB_LEN];
+    data[0..4].copy_from_slice(&job.id.to_be_bytes());
+    data[4..24].copy_from_slice(&job.client_id);
+    data[24..40].copy_from_slice(&job.budget.to_be_bytes());
+    data[40] = job.status as u8;
+    data[41..73].copy_from_slice(&job.content_cid);
+    data[73..77].copy_from_slice(&job.category.to_be_bytes());
+    data[77..85].copy_from_slice(&job.deadline.to_be_bytes());
+    data[85..93].copy_from_slice(&job.bidding_deadline.to_be_bytes());
+    data[93..113].copy_from_slice(&job.payment_token);
+    data[113..129].copy_from_slice(&job.min_budget.to_be_bytes());
+    write_record(host, &record_key(PREFIX_JOB, job.id), &data);
+}
+
+// --- Proposals ---
+
+pub fn load_proposal(host: &impl Host, proposal_id: ProposalId) -> Option<Proposal> {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    let (version, data) = read_record(host, &record_key(PREFIX_PROPOSAL, proposal_id), &mut buf, PROPOSAL_V4_LEN)?;
+    let (cover_hash, round_count) = match (version, data.len()) {
+        (1..=4, PROPOSAL_V4_LEN) => ([0u8; 32], 0), // Submitted before proposals had a cover letter
+        (5, PROPOSAL_V5_LEN) => (data[53..85].try_into().unwrap(), 0), // Before counter-offers
+        (6.., PROPOSAL_LEN) => (data[53..85].try_into().unwrap(), read_u32(data, 85)),
+        _ => return None,
+    };
+    Some(Proposal {
+        id: read_u32(data, 0),
+        job_id: read_u32(data, 4),
+        freelancer_id: read_address(data, 8),
+        bid_amount: read_u128(data, 28),
+        status: ProposalStatus::from_u8(data[44])?,
+        expires_at: read_u64(data, 45),
+        cover_hash,
+        round_count,
+    })
+}
+
+pub fn save_proposal(host: &mut impl Host, proposal: &Proposal) {
+    let mut data = [0u8; PROPOSAL_LEN];
+    data[0..4].copy_from_slice(&proposal.id.to_be_bytes());
+    data[4..8].copy_from_slice(&proposal.job_id.to_be_bytes());
+    data[8..28].copy_from_slice(&proposal.freelancer_id);
+    data[28..44].copy_from_slice(&proposal.bid_amount.to_be_bytes());
+    data[44] = proposal.status as u8;
+    data[45..53].copy_from_slice(&proposal.expires_at.to_be_bytes());
+    data[53..85].copy_from_slice(&proposal.cover_hash);
+    data[85..89].copy_from_slice(&proposal.round_count.to_be_bytes());
+    write_record(host, &record_key(PREFIX_PROPOSAL, proposal.id), &data);
+}
+
+/// The proposal `freelancer` submitted for `job_id`, if any.
+pub fn proposal_of(host: &impl Host, job_id: JobId, freelancer: &Address) -> Option<ProposalId> {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    let data = read(host, &job_address_key(PREFIX_PROPOSAL_INDEX, job_id, freelancer), &mut buf, PROPOSAL_INDEX_LEN)?;
+    Some(read_u32(data, 0))
+}
+
+pub fn set_proposal_of(host: &mut impl Host, job_id: JobId, freelancer: &Address, proposal_id: ProposalId) {
+    write(host, &job_address_key(PREFIX_PROPOSAL_INDEX, job_id, freelancer), &proposal_id.to_be_bytes());
+}
+
+pub fn load_counter_offer(host: &impl Host, proposal_id: ProposalId, round: u32) -> Option<CounterOffer> {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    let data = read(host, &child_key(PREFIX_COUNTER_OFFER, proposal_id, round), &mut buf, COUNTER_OFFER_LEN)?;
+    Some(CounterOffer {
+        proposal_id,
+        round,
+        from: read_address(data, 0),
+        amount: read_u128(data, 20),
+        terms_hash: data[36..68].try_into().unwrap(),
+    })
+}
+
+pub fn save_counter_offer(host: &mut impl Host, offer: &CounterOffer) {
+    let mut data = [0u8; COUNTER_OFFER_LEN];
+    data[0..20].copy_from_slice(&offer.from);
+    data[20..36].copy_from_slice(&offer.amount.to_be_bytes());
+    data[36..68].copy_from_slice(&offer.terms_hash);
+    write(host, &child_key(PREFIX_COUNTER_OFFER, offer.proposal_id, offer.round), &data);
+}
+
+// --- Agreements ---
+
+pub fn load_agreement(host: &impl Host, agreement_id: AgreementId) -> Option<Agreement> {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    let (version, data) = read_record(host, &record_key(PREFIX_AGREEMENT, agreement_id), &mut buf, AGREEMENT_V2_LEN)?;
+    let fee_bps = match (version, data.len()) {
+        (1 | 2, AGREEMENT_V2_LEN) => 0, // Agreed before there was a fee
+        (3.., AGREEMENT_LEN) => u16::from_be_bytes([data[69], data[70]]),
+        _ => return None,
+    };
+    Some(Agreement {
+        id: read_u32(data, 0),
+        job_id: read_u32(data, 4),
+        client_id: read_address(data, 8),
+        freelancer_id: read_address(data, 28),
+        total_amount: read_u128(data, 48),
+        status: AgreementStatus::from_u8(data[64])?,
+        milestone_count: read_u32(data, 65),
+        fee_bps,
+    })
+}
+
+pub fn save_agreement(host: &mut impl Host, agreement: &Agreement) {
+    let mut data = [0u8; AGREEMENT_LEN];
+    data[0..4].copy_from_slice(&agreement.id.to_be_bytes());
+    data[4..8].copy_from_slice(&agreement.job_id.to_be_bytes());
+    data[8..28].copy_from_slice(&agreement.client_id);
+    data[28..48].copy_from_slice(&agreement.freelancer_id);
+    data[48..64].copy_from_slice(&agreement.total_amount.to_be_bytes());
+    data[64] = agreement.status as u8;
+    data[65..69].copy_from_slice(&agreement.milestone_count.to_be_bytes());
+    data[69..71].copy_from_slice(&agreement.fee_bps.to_be_bytes());
+    write_record(host, &record_key(PREFIX_AGREEMENT, agreement.id), &data);
+}
+
+// --- Milestones ---
+
+pub fn load_milestone(host: &impl Host, agreement_id: AgreementId, index: MilestoneIndex) -> Option<Milestone> {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    let data = read(host, &child_key(PREFIX_MILESTONE, agreement_id, index), &mut buf, MILESTONE_LEN)?;
+    Some(Milestone {
+        agreement_id,
+        index,
+        amount: read_u128(data, 0),
+        description_hash: data[16..48].try_into().unwrap(),
+        status: MilestoneStatus::from_u8(data[48])?,
+    })
+}
+
+pub fn save_milestone(host: &mut impl Host, milestone: &Milestone) {
+    let mut data = [0u8; MILESTONE_LEN];
+    data[0..16].copy_from_slice(&milestone.amount.to_be_bytes());
+    data[16..48].copy_from_slice(&milestone.description_hash);
+    data[48] = milestone.status as u8;
+    write(host, &child_key(PREFIX_MILESTONE, milestone.agreement_id, milestone.index), &data);
+}
+
+// --- Reputation ---
+
+/// Reputation of `account`; all zero if it never took part in an agreement.
+pub fn load_reputation(host: &impl Host, account: &Address) -> Reputation {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    match read(host, &address_key(PREFIX_REPUTATION, account), &mut buf, REPUTATION_LEN) {
+        Some(data) => Reputation {
+            rating_count: read_u32(data, 0),
+            rating_sum: read_u32(data, 4),
+            completed_jobs: read_u32(data, 8),
+            disputes: read_u32(data, 12),
+        },
+        None => Reputation::default(),
+    }
+}
+
+pub fn save_reputation(host: &mut impl Host, account: &Address, reputation: &Reputation) {
+    let mut data = [0u8; REPUTATION_LEN];
+    data[0..4].copy_from_slice(&reputation.rating_count.to_be_bytes());
+    data[4..8].copy_from_slice(&reputation.rating_sum.to_be_bytes());
+    data[8..12].copy_from_slice(&reputation.completed_jobs.to_be_bytes());
+    data[12..16].copy_from_slice(&reputation.disputes.to_be_bytes());
+    write(host, &address_key(PREFIX_REPUTATION, account), &data);
+}
+
+/// The review left on `agreement_id` by one side (reputation::CLIENT_SIDE or FREELANCER_SIDE).
+/// Returns (score, review_hash), or None if that side has not rated yet.
+pub fn load_review(host: &impl Host, agreement_id: AgreementId, side: u32) -> Option<(u8, [u8; 32])> {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    let data = read(host, &child_key(PREFIX_REVIEW, agreement_id, side), &mut buf, REVIEW_LEN)?;
+    Some((data[0], data[1..33].try_into().unwrap()))
+}
+
+pub fn save_review(host: &mut impl Host, agreement_id: AgreementId, side: u32, score: u8, review_hash: &[u8; 32]) {
+    let mut data = [0u8; REVIEW_LEN];
+    data[0] = score;
+    data[1..33].copy_from_slice(review_hash);
+    write(host, &child_key(PREFIX_REVIEW, agreement_id, side), &data);
+}
+
+// --- Escrow ---
+
+/// Balance held in escrow for `job_id`, in the job's payment token.
+pub fn escrow_balance(host: &impl Host, job_id: JobId) -> Balance {
+    let mut buf = [0u8; MAX_RECORD_LEN];
+    match read(host, &record_key(PREFIX_ESCROW, job_id), &mut buf, ESCROW_LEN) {
+        Some(data) => read_u128(data, 0),
+        None => 0,
+    }
+}
+
+pub fn set_escrow_balance(host: &mut impl Host, job_id: JobId, balance: Balance) {
+    write(host, &record_key(PREFIX_ESCROW, job_id), &balance.to_be_bytes());
+}
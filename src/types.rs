@@ -1,10 +1,13 @@
 // filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/types.rs
-#![cfg_attr(not(feature = "std"), no_std)]
 
 pub type JobId = u32;
 pub type ProposalId = u32;
 pub type AgreementId = u32;
-pub type UserId = u32;
+/// Position of a milestone within its agreement's schedule, starting at 0.
+pub type MilestoneIndex = u32;
+/// H160 account address, as seen by Solidity (`msg.sender`).
+/// Identities are always taken from `api::caller()`, never from call arguments.
+pub type Address = [u8; 20];
 pub type Balance = u128;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -22,6 +25,7 @@ pub enum ProposalStatus {
     Submitted = 0,
     Accepted = 1,
     Rejected = 2,
+    Withdrawn = 3, // Retracted by the freelancer before the client decided
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -30,17 +34,44 @@ pub enum AgreementStatus {
     Active = 0,
     Completed = 1,
     Disputed = 2,
+    Resolved = 3, // Dispute settled by the arbitrator
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum MilestoneStatus {
+    Pending = 0,
+    Submitted = 1,
+    Approved = 2,
+    Paid = 3,
 }
 
 // Simplified Job structure
+// Title and description live off-chain; content_cid commits to them so they can be verified.
 #[derive(Debug, Clone, Copy)]
 pub struct Job {
     pub id: JobId,
-    pub client_id: UserId,
-    pub budget: Balance,
+    pub client_id: Address,
+    pub budget: Balance, // Most the client means to pay; higher bids are flagged as over budget
     pub status: JobStatus,
-    // For simplicity in ABI encoding, detailed fields like title/description are omitted.
-    // In a real scenario, these would be handled, possibly via IPFS hashes or byte arrays.
+    pub content_cid: [u8; 32], // sha2-256 digest of the IPFS CIDv1 holding title/description
+    pub category: u32,
+    pub deadline: u64, // Delivery deadline, unix seconds (0 = none)
+    pub bidding_deadline: u64, // No proposals after this, unix seconds (0 = none)
+    pub payment_token: Address, // ERC-20 contract the job is paid in (zero = native token)
+    pub min_budget: Balance, // Lower bids are rejected
+}
+
+// What the client specifies when posting a job (the createJob arguments).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JobParams {
+    pub budget: Balance,
+    pub content_cid: [u8; 32],
+    pub category: u32,
+    pub deadline: u64,
+    pub bidding_deadline: u64,
+    pub payment_token: Address,
+    pub min_budget: Balance,
 }
 
 // Simplified Proposal structure
@@ -48,9 +79,23 @@ pub struct Job {
 pub struct Proposal {
     pub id: ProposalId,
     pub job_id: JobId,
-    pub freelancer_id: UserId,
+    pub freelancer_id: Address,
     pub bid_amount: Balance,
     pub status: ProposalStatus,
+    pub expires_at: u64, // The client can no longer accept it after this, unix seconds (0 = never)
+    pub cover_hash: [u8; 32], // sha2-256 digest of the IPFS CIDv1 holding the cover letter
+    pub round_count: u32, // Counter-offers made so far
+}
+
+// One round of negotiating a proposal's price. The client makes round 0, then the
+// freelancer and the client alternate.
+#[derive(Debug, Clone, Copy)]
+pub struct CounterOffer {
+    pub proposal_id: ProposalId,
+    pub round: u32,
+    pub from: Address,
+    pub amount: Balance,
+    pub terms_hash: [u8; 32], // e.g. an IPFS digest of the revised terms
 }
 
 // Simplified Agreement structure
@@ -58,21 +103,59 @@ pub struct Proposal {
 pub struct Agreement {
     pub id: AgreementId,
     pub job_id: JobId,
-    pub client_id: UserId,
-    pub freelancer_id: UserId,
+    pub client_id: Address,
+    pub freelancer_id: Address,
     pub total_amount: Balance,
     pub status: AgreementStatus,
+    pub milestone_count: u32,
+    pub fee_bps: u16, // Platform fee on each payout, fixed when the agreement is made
+}
+
+// One installment of an agreement. The amounts of all milestones add up to total_amount.
+#[derive(Debug, Clone, Copy)]
+pub struct Milestone {
+    pub agreement_id: AgreementId,
+    pub index: MilestoneIndex,
+    pub amount: Balance,
+    pub description_hash: [u8; 32], // e.g. an IPFS digest of the deliverable spec
+    pub status: MilestoneStatus,
 }
 
-// Basic error type
-#[repr(u32)]
+// Admin-tunable parameters, stored once in deploy() and changed with setConfig.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    pub arbitrator: Address, // Rules on disputed agreements
+    pub fee_bps: u16, // Platform fee, in basis points
+    pub min_job_budget: Balance,
+    pub max_milestones: u32, // Per agreement, at most agreement_management::MAX_MILESTONES
+}
+
+// Aggregate reputation of one account, across all agreements it took part in.
+// The average rating is rating_sum / rating_count.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Reputation {
+    pub rating_count: u32,
+    pub rating_sum: u32,
+    pub completed_jobs: u32,
+    pub disputes: u32,
+}
+
+// Contract errors. Each variant is returned to callers as a Solidity custom error
+// (see errors.rs and the `error` declarations in call_from_sol.sol).
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ContractError {
-    InvalidOperation = 1,
-    NotFound = 2,
-    AlreadyExists = 3,
-    StorageFull = 4,
-    InvalidInput = 5,
-    Unauthorized = 6,
+    InvalidOperation,
+    NotFound(u32), // id of the missing job/proposal/agreement
+    AlreadyExists,
+    StorageFull,
+    InvalidInput,
+    Unauthorized(Address), // the caller that was refused
+    TransferFailed(Address, Balance), // recipient, amount
+    InvalidTransition(u8, u8), // from status, to status
+    InsufficientEscrow(Balance, Balance), // available, required
+    UnknownSelector(u32),
+    DeadlinePassed(u64), // the deadline, unix seconds
+    EnforcedPause, // the contract is paused
 }
 
 impl JobStatus {
@@ -93,6 +176,7 @@ impl ProposalStatus {
             0 => Some(ProposalStatus::Submitted),
             1 => Some(ProposalStatus::Accepted),
             2 => Some(ProposalStatus::Rejected),
+            3 => Some(ProposalStatus::Withdrawn),
             _ => None,
         }
     }
@@ -104,6 +188,19 @@ impl AgreementStatus {
             0 => Some(AgreementStatus::Active),
             1 => Some(AgreementStatus::Completed),
             2 => Some(AgreementStatus::Disputed),
+            3 => Some(AgreementStatus::Resolved),
+            _ => None,
+        }
+    }
+}
+
+impl MilestoneStatus {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MilestoneStatus::Pending),
+            1 => Some(MilestoneStatus::Submitted),
+            2 => Some(MilestoneStatus::Approved),
+            3 => Some(MilestoneStatus::Paid),
             _ => None,
         }
     }
@@ -0,0 +1,284 @@
+// filepath: /home/nathfavour/Documents/code/web3lancer/web3lancer/contracts/polkadot-contract/src/upgrade.rs
+
+// In-place code upgrades and storage migrations.
+// The owner swaps the contract's code with `upgrade`; the address, balance and storage stay.
+// Job, proposal and agreement records carry a layout version byte (see storage::STORAGE_VERSION).
+// New code reads older records as it goes and rewrites them on their next save, and `migrate`
+// rewrites the rest in batches so no single call has to touch every record. It also builds the
+// (job, freelancer) proposal index for proposals submitted before the index existed.
+
+use crate::types::{Address, ContractError, ProposalStatus};
+use crate::host::Host;
+use crate::storage::{self, STORAGE_VERSION};
+use crate::events;
+use crate::admin;
+use crate::proposal_management;
+
+// Record kinds in the order `migrate` walks them
+const KIND_JOBS: u8 = 0;
+const KIND_PROPOSALS: u8 = 1;
+const KIND_AGREEMENTS: u8 = 2;
+const KIND_DONE: u8 = 3;
+
+/// Replaces the contract code with `code_hash`, which must already be uploaded. Owner only.
+/// If the new code bumps the storage version, follow up with `migrate` until it reports done.
+/// Input: caller (Address), code_hash ([u8; 32])
+/// Output: 0 on success or error code
+pub fn upgrade(host: &mut impl Host, caller: Address, code_hash: [u8; 32]) -> Result<(), ContractError> {
+    admin::ensure_owner(host, &caller)?;
+    events::upgraded(host, &code_hash);
+    host.set_code_hash(&code_hash);
+    Ok(())
+}
+
+/// Rewrites up to `max_records` jobs, proposals and agreements into the current layout,
+/// continuing where the previous batch stopped. Once every record is rewritten the stored
+/// version becomes STORAGE_VERSION. Owner only.
+/// A record that cannot be decoded fails the batch, so the migration never completes past it.
+/// Input: caller (Address), max_records (u32)
+/// Output: (records rewritten in this batch, whether the migration is complete) or error code
+pub fn migrate(host: &mut impl Host, caller: Address, max_records: u32) -> Result<(u32, bool), ContractError> {
+    admin::ensure_owner(host, &caller)?;
+    if storage::storage_version(host) == STORAGE_VERSION {
+        return Ok((0, true)); // Nothing to do
+    }
+
+    let (mut kind, mut next_id) = storage::migration_cursor(host);
+    let mut migrated = 0;
+    while kind < KIND_DONE && migrated < max_records {
+        if next_id >= record_count(host, kind) {
+            kind += 1;
+            next_id = 0;
+            continue;
+        }
+        rewrite_record(host, kind, next_id)?;
+        next_id += 1;
+        migrated += 1;
+    }
+
+    // A batch that ends exactly on the last record still has to look at the remaining kinds
+    while kind < KIND_DONE && next_id >= record_count(host, kind) {
+        kind += 1;
+        next_id = 0;
+    }
+
+    if kind < KIND_DONE {
+        storage::set_migration_cursor(host, kind, next_id);
+        return Ok((migrated, false));
+    }
+    storage::set_migration_cursor(host, 0, 0); // Ready for the next version bump
+    storage::set_storage_version(host, STORAGE_VERSION);
+    events::storage_migrated(host, STORAGE_VERSION);
+    Ok((migrated, true))
+}
+
+/// Gets the version the stored records are in and the version this code writes.
+/// Output: (stored_version, code_version)
+pub fn get_storage_version(host: &impl Host) -> (u8, u8) {
+    (storage::storage_version(host), STORAGE_VERSION)
+}
+
+fn record_count(host: &impl Host, kind: u8) -> u32 {
+    match kind {
+        KIND_JOBS => storage::next_job_id(host),
+        KIND_PROPOSALS => storage::next_proposal_id(host),
+        KIND_AGREEMENTS => storage::next_agreement_id(host),
+        _ => 0,
+    }
+}
+
+/// Loading converts the record from whatever version it is in; saving writes the current one.
+/// Every id below the kind's counter was written when it was handed out, so a record that
+/// fails to load has an unknown length or version and is reported rather than skipped.
+/// Proposals are walked in id order and every one that was not withdrawn takes over the index,
+/// so it ends up on a freelancer's latest proposal that was not withdrawn (or on the latest
+/// withdrawn one if that is all there is).
+fn rewrite_record(host: &mut impl Host, kind: u8, id: u32) -> Result<(), ContractError> {
+    match kind {
+        KIND_JOBS => {
+            let job = storage::load_job(host, id).ok_or(ContractError::InvalidOperation)?;
+            storage::save_job(host, &job);
+        }
+        KIND_PROPOSALS => {
+            let proposal = storage::load_proposal(host, id).ok_or(ContractError::InvalidOperation)?;
+            storage::save_proposal(host, &proposal);
+            if proposal.status != ProposalStatus::Withdrawn
+                || !proposal_management::holds_proposal(host, proposal.job_id, &proposal.freelancer_id)
+            {
+                storage::set_proposal_of(host, proposal.job_id, &proposal.freelancer_id, id);
+            }
+        }
+        KIND_AGREEMENTS => {
+            let agreement = storage::load_agreement(host, id).ok_or(ContractError::InvalidOperation)?;
+            storage::save_agreement(host, &agreement);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agreement_management;
+    use crate::job_management;
+    use crate::mock::{job_params, MockHost};
+
+    const CLIENT: Address = [1u8; 20];
+    const FREELANCER: Address = [2u8; 20];
+    const OWNER: Address = [9u8; 20];
+    const STRANGER: Address = [7u8; 20];
+
+    /// Two jobs, a proposal and an agreement, stored the way version 1 code left them:
+    /// no version byte, no payment_token or min_budget on the jobs, no cover_hash or round_count on the proposal,
+    /// no fee_bps on the agreement, no proposal index and no storage version slot.
+    fn legacy_host() -> MockHost {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        job_management::create_job(&mut host, CLIENT, &job_params(1_000), 1_000).unwrap();
+        job_management::create_job(&mut host, CLIENT, &job_params(500), 0).unwrap();
+        proposal_management::submit_proposal(&mut host, 0, FREELANCER, 800, 0, [0u8; 32]).unwrap();
+        proposal_management::update_proposal_status(&mut host, CLIENT, 0, ProposalStatus::Accepted as u8).unwrap();
+        agreement_management::create_agreement_from_proposal(&mut host, CLIENT, 0, &[]).unwrap();
+
+        for (key, value) in host.storage.iter_mut() {
+            if matches!(key[0], 0x01..=0x03) {
+                value.remove(0);
+            }
+            if key[0] == 0x01 {
+                value.truncate(value.len() - 36);
+            }
+            if key[0] == 0x02 {
+                value.truncate(value.len() - 36);
+            }
+            if key[0] == 0x03 {
+                value.truncate(value.len() - 2);
+            }
+        }
+        host.storage.retain(|key, _| key[0] != 0x0a && !(key[0] == 0x11 && key[1] == 0x05));
+        host
+    }
+
+    fn legacy_records(host: &MockHost) -> usize {
+        host.storage.iter().filter(|(key, value)| matches!(key[0], 0x01..=0x03) && value[0] != STORAGE_VERSION).count()
+    }
+
+    #[test]
+    fn only_owner_upgrades() {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        assert_eq!(upgrade(&mut host, STRANGER, [0xab; 32]), Err(ContractError::Unauthorized(STRANGER)));
+        assert_eq!(host.code_hash, None);
+
+        upgrade(&mut host, OWNER, [0xab; 32]).unwrap();
+        assert_eq!(host.code_hash, Some([0xab; 32]));
+        assert_eq!(host.events.last().unwrap().topics, vec![events::UPGRADED, [0xab; 32]]);
+    }
+
+    #[test]
+    fn fresh_deploy_needs_no_migration() {
+        let mut host = MockHost::new();
+        host.deploy(OWNER);
+        assert_eq!(get_storage_version(&host), (STORAGE_VERSION, STORAGE_VERSION));
+        assert_eq!(migrate(&mut host, OWNER, 10), Ok((0, true)));
+    }
+
+    #[test]
+    fn legacy_records_are_read_and_rewritten_lazily() {
+        let mut host = legacy_host();
+        assert_eq!(get_storage_version(&host), (1, STORAGE_VERSION));
+        assert_eq!(legacy_records(&host), 4);
+
+        assert_eq!(job_management::get_job(&host, 1).unwrap().1, 500);
+        assert_eq!(proposal_management::get_proposal(&host, 0).unwrap().2, 800);
+        agreement_management::submit_milestone(&mut host, FREELANCER, 0, 0).unwrap();
+        job_management::update_job_status(&mut host, CLIENT, 1, 3).unwrap();
+        assert_eq!(legacy_records(&host), 3); // Only job 1 was saved again
+    }
+
+    #[test]
+    fn agreements_from_before_fees_pay_none() {
+        let mut host = legacy_host();
+        assert_eq!(storage::load_agreement(&host, 0).unwrap().fee_bps, 0);
+
+        // Version 2: version byte, still no fee_bps
+        let key = host.storage.keys().find(|key| key[0] == 0x03).copied().unwrap();
+        host.storage.get_mut(&key).unwrap().insert(0, 2);
+        assert_eq!(storage::load_agreement(&host, 0).unwrap().fee_bps, 0);
+        assert_eq!(agreement_management::get_payout_breakdown(&host, 0), Ok((800, 0, 0, 800, 0)));
+    }
+
+    #[test]
+    fn migrate_rewrites_records_in_batches() {
+        let mut host = legacy_host();
+        assert_eq!(migrate(&mut host, STRANGER, 10), Err(ContractError::Unauthorized(STRANGER)));
+
+        assert_eq!(migrate(&mut host, OWNER, 3), Ok((3, false))); // Both jobs and the proposal
+        assert_eq!(legacy_records(&host), 1);
+        assert_eq!(storage::storage_version(&host), 1);
+
+        assert_eq!(migrate(&mut host, OWNER, 3), Ok((1, true)));
+        assert_eq!(legacy_records(&host), 0);
+        assert_eq!(get_storage_version(&host), (STORAGE_VERSION, STORAGE_VERSION));
+        assert_eq!(storage::migration_cursor(&host), (0, 0));
+        assert_eq!(host.events.last().unwrap().topics, vec![events::STORAGE_MIGRATED]);
+        assert_eq!(agreement_management::get_agreement(&host, 0).unwrap().3, 800);
+    }
+
+    #[test]
+    fn batch_ending_on_last_record_completes() {
+        let mut host = legacy_host();
+        assert_eq!(migrate(&mut host, OWNER, 4), Ok((4, true)));
+    }
+
+    /// Version 1 allowed several bids from one freelancer on the same job: adds proposals
+    /// 1, 2, ... from FREELANCER on job 1 with the given statuses.
+    fn add_legacy_bids(host: &mut MockHost, statuses: &[ProposalStatus]) {
+        let key = host.storage.keys().find(|key| key[0] == 0x02).copied().unwrap();
+        for (i, status) in statuses.iter().enumerate() {
+            let id = i as u8 + 1;
+            let mut value = host.storage[&key].clone();
+            value[3] = id; // id
+            value[7] = 1; // job_id
+            value[44] = *status as u8;
+            let mut new_key = key;
+            new_key[31] = id;
+            host.storage.insert(new_key, value);
+        }
+        storage::set_next_proposal_id(host, statuses.len() as u32 + 1);
+    }
+
+    #[test]
+    fn migrate_indexes_existing_proposals() {
+        let mut host = legacy_host();
+        add_legacy_bids(&mut host, &[ProposalStatus::Withdrawn, ProposalStatus::Submitted, ProposalStatus::Withdrawn]);
+        assert_eq!(proposal_management::get_proposal_by_freelancer(&host, 0, &FREELANCER), Ok((false, 0)));
+
+        assert_eq!(migrate(&mut host, OWNER, 10), Ok((7, true)));
+        assert_eq!(proposal_management::get_proposal_by_freelancer(&host, 0, &FREELANCER), Ok((true, 0)));
+        assert_eq!(proposal_management::get_proposal_by_freelancer(&host, 1, &FREELANCER), Ok((true, 2)));
+        let result = proposal_management::submit_proposal(&mut host, 1, FREELANCER, 400, 0, [0u8; 32]);
+        assert_eq!(result, Err(ContractError::AlreadyExists));
+    }
+
+    #[test]
+    fn migrate_indexes_the_latest_of_duplicate_bids() {
+        let mut host = legacy_host();
+        add_legacy_bids(&mut host, &[ProposalStatus::Submitted, ProposalStatus::Submitted]);
+
+        assert_eq!(migrate(&mut host, OWNER, 10), Ok((6, true)));
+        assert_eq!(proposal_management::get_proposal_by_freelancer(&host, 1, &FREELANCER), Ok((true, 2)));
+    }
+
+    #[test]
+    fn undecodable_record_stops_the_migration() {
+        let mut host = legacy_host();
+        let key = host.storage.keys().find(|key| key[0] == 0x02).copied().unwrap();
+        host.storage.get_mut(&key).unwrap().truncate(10);
+
+        assert_eq!(migrate(&mut host, OWNER, 2), Ok((2, false))); // Both jobs
+        assert_eq!(migrate(&mut host, OWNER, 2), Err(ContractError::InvalidOperation));
+        assert_eq!(storage::migration_cursor(&host), (KIND_PROPOSALS, 0));
+        assert_eq!(get_storage_version(&host), (1, STORAGE_VERSION));
+    }
+}